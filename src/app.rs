@@ -1,47 +1,141 @@
-use crate::models::{SystemMetrics, UserConfig};
-use crate::services::{ConfigManager, SystemMonitor};
-use std::time::{Duration, Instant};
-use crate::ui::render_widget;
+use crate::models::{Metric, MetricHistory, SystemMetrics, UserConfig};
+use crate::services::{
+    AlertEngine, CommandRunner, ConfigManager, Notifier, RuleStatus, Sampler, SystemMonitor,
+};
+use crate::ui::{DetailSection, SUMMARY_WINDOW, render_detail, render_widget};
+use std::sync::{Arc, Mutex, PoisonError, mpsc};
+use std::time::Duration;
 
 pub struct PerchApp {
-    monitor: SystemMonitor,
+    sampler: Sampler,
     metrics: SystemMetrics,
+    /// Recorded and evaluated on the sampler's thread, so they see every
+    /// sample even while nothing is drawn; the UI only reads them.
+    history: Arc<Mutex<MetricHistory>>,
+    alerts: Arc<Mutex<AlertEngine>>,
+    /// What frames are drawn from, copied out of the two above once per
+    /// sample so that drawing never holds up the sampler.
+    shown_history: MetricHistory,
+    firing: Vec<RuleStatus>,
+    /// The window and sample count `shown_history` was copied with.
+    shown_span: (Duration, usize),
+    clicks: mpsc::Receiver<Metric>,
     config: UserConfig,
     config_manager: ConfigManager,
     show_settings: bool,
-    last_update: Instant,
+    detail: Option<DetailSection>,
+    focus_detail: bool,
     positioned: bool,
 }
 
 impl PerchApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         let config_manager = ConfigManager::new(
             ConfigManager::default_path().expect("Could not determine config path"),
         );
         let config = config_manager.load().unwrap_or_default();
 
-        let monitor = SystemMonitor::new();
+        let monitor = SystemMonitor::from_config(&config);
+
+        let history = Arc::new(Mutex::new(MetricHistory::new(Duration::from_secs(
+            config.history_retention_secs,
+        ))));
+        let alerts = Arc::new(Mutex::new(AlertEngine::new(config.alert_rules.clone())));
+        let mut commands = CommandRunner::new(config.alert_commands);
+        let (clicked, clicks) = mpsc::channel();
+        let notifier = config.notifications.enabled.then(|| {
+            let ctx = cc.egui_ctx.clone();
+            let on_click = move |metric| {
+                let _ = clicked.send(metric);
+                ctx.request_repaint();
+            };
+            Notifier::connect(config.notifications.click_to_open, on_click)
+                .map_err(|e| eprintln!("Desktop notifications unavailable: {}", e))
+                .ok()
+        });
+        let notifier = notifier.flatten();
+
+        let recorded = Arc::clone(&history);
+        let engine = Arc::clone(&alerts);
+        let on_collect = move |metrics: &SystemMetrics| {
+            recorded
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .record(metrics);
+            let events = engine
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .evaluate(metrics);
+            for event in events {
+                eprintln!("{}", event);
+                // Commands are watched on their own threads; nothing here
+                // needs to wait for them.
+                let _ = commands.run(&event);
+                if let Some(notifier) = &notifier {
+                    if let Err(e) = notifier.notify(&event) {
+                        eprintln!("Failed to send notification: {}", e);
+                    }
+                }
+            }
+        };
+
+        let ctx = cc.egui_ctx.clone();
+        let sampler = Sampler::spawn(monitor, config.refresh_interval(), on_collect, move || {
+            ctx.request_repaint()
+        })
+        .expect("Could not start metric sampler");
         let metrics = SystemMetrics::zero();
+        let shown_history = MetricHistory::new(Duration::from_secs(config.history_retention_secs));
 
         Self {
-            monitor,
+            sampler,
             metrics,
+            history,
+            alerts,
+            shown_history,
+            firing: Vec::new(),
+            shown_span: (Duration::ZERO, 0),
+            clicks,
             config,
             config_manager,
             show_settings: false,
-            last_update: Instant::now(),
+            detail: None,
+            focus_detail: false,
             positioned: false,
         }
     }
 
     fn update_metrics(&mut self) {
-        let elapsed = self.last_update.elapsed();
-        if elapsed >= Duration::from_secs_f32(self.config.refresh_interval_secs) {
-            self.metrics = self.monitor.collect();
-            self.last_update = Instant::now();
+        let sampled = match self.sampler.latest() {
+            Some(metrics) => {
+                self.metrics = metrics;
+                true
+            }
+            None => false,
+        };
+
+        let span = self.history_span();
+        if sampled || span != self.shown_span {
+            let history = self.history.lock().unwrap_or_else(PoisonError::into_inner);
+            self.shown_history = history.copy_recent(span.0, span.1);
+            drop(history);
+
+            let alerts = self.alerts.lock().unwrap_or_else(PoisonError::into_inner);
+            self.firing = alerts.firing().cloned().collect();
+            self.shown_span = span;
         }
     }
 
+    /// How much history a frame can draw from: the header's summaries, the
+    /// sparklines, and the detail window's range while it's open.
+    fn history_span(&self) -> (Duration, usize) {
+        let mut window = SUMMARY_WINDOW;
+        if self.detail.is_some() {
+            window = window.max(self.config.detail_range.duration());
+        }
+        (window, self.config.sparkline_samples)
+    }
+
     fn save_config(&self) {
         if let Err(e) = self.config_manager.save(&self.config) {
             eprintln!("Failed to save config: {}", e);
@@ -49,10 +143,7 @@ impl PerchApp {
     }
 
     fn apply_window_position(&self, ctx: &egui::Context) {
-        let pos = egui::Pos2::new(
-            self.config.window_position.x,
-            self.config.window_position.y,
-        );
+        let pos = egui::Pos2::new(self.config.window_position.x, self.config.window_position.y);
 
         ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(pos));
     }
@@ -60,6 +151,11 @@ impl PerchApp {
 
 impl eframe::App for PerchApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if let Ok(metric) = self.clicks.try_recv() {
+            self.detail = Some(DetailSection::for_metric(metric));
+            self.focus_detail = true;
+        }
+
         self.update_metrics();
 
         if !self.positioned {
@@ -83,7 +179,33 @@ impl eframe::App for PerchApp {
             }
         }
 
-        render_widget(ctx, &self.metrics, &self.config, &mut self.show_settings);
-        ctx.request_repaint_after(Duration::from_secs_f32(self.config.refresh_interval_secs));
+        let previous_config = self.config.clone();
+        render_widget(
+            ctx,
+            &self.metrics,
+            &self.shown_history,
+            &self.firing,
+            &mut self.config,
+            &mut self.show_settings,
+            &mut self.detail,
+        );
+        if let Some(section) = &mut self.detail {
+            let open = render_detail(
+                ctx,
+                section,
+                &self.metrics,
+                &self.shown_history,
+                &mut self.config,
+                self.focus_detail,
+            );
+            self.focus_detail = false;
+            if !open {
+                self.detail = None;
+            }
+        }
+        if self.config != previous_config {
+            self.sampler.apply_config(&self.config);
+            self.save_config();
+        }
     }
 }
@@ -0,0 +1,310 @@
+use crate::models::Metric;
+use anyhow::{Result, anyhow, bail};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Comparison {
+    Above,
+    Below,
+}
+
+impl Comparison {
+    fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Above => ">",
+            Comparison::Below => "<",
+        }
+    }
+}
+
+/// When a rule triggers, written in the config as e.g.
+/// `cpu.total > 90 for 30s` or `mem.used_pct > 85`. Durations take an
+/// `s`, `m` or `h` suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct AlertCondition {
+    pub metric: Metric,
+    pub comparison: Comparison,
+    pub threshold: f32,
+    /// How long the threshold must stay crossed before the rule fires.
+    pub duration: Duration,
+}
+
+impl AlertCondition {
+    /// Whether `value` is past the threshold.
+    pub fn is_breached(&self, value: f32) -> bool {
+        match self.comparison {
+            Comparison::Above => value > self.threshold,
+            Comparison::Below => value < self.threshold,
+        }
+    }
+
+    /// Whether `value` is back far enough on the safe side of the threshold
+    /// for a firing rule to resolve, `hysteresis` past it.
+    pub fn is_cleared(&self, value: f32, hysteresis: f32) -> bool {
+        match self.comparison {
+            Comparison::Above => value <= self.threshold - hysteresis,
+            Comparison::Below => value >= self.threshold + hysteresis,
+        }
+    }
+}
+
+impl FromStr for AlertCondition {
+    type Err = anyhow::Error;
+
+    fn from_str(condition: &str) -> Result<Self> {
+        let words: Vec<&str> = condition.split_whitespace().collect();
+        let (metric, comparison, threshold, duration) = match words.as_slice() {
+            [metric, comparison, threshold] => (metric, comparison, threshold, None),
+            [metric, comparison, threshold, "for", duration] => {
+                (metric, comparison, threshold, Some(duration))
+            }
+            _ => bail!(
+                "Expected `<metric> <op> <threshold> [for <duration>]`: {}",
+                condition
+            ),
+        };
+
+        let metric = Metric::from_key(metric)
+            .ok_or_else(|| anyhow!("Unknown metric `{}` in alert rule", metric))?;
+        let comparison = match *comparison {
+            ">" => Comparison::Above,
+            "<" => Comparison::Below,
+            other => bail!("Unknown comparison `{}` in alert rule, use > or <", other),
+        };
+        let threshold = threshold
+            .parse()
+            .ok()
+            .filter(|threshold: &f32| threshold.is_finite())
+            .ok_or_else(|| anyhow!("Invalid threshold `{}` in alert rule", threshold))?;
+        let duration = match duration {
+            Some(duration) => parse_duration(duration)?,
+            None => Duration::ZERO,
+        };
+
+        Ok(Self {
+            metric,
+            comparison,
+            threshold,
+            duration,
+        })
+    }
+}
+
+impl TryFrom<String> for AlertCondition {
+    type Error = anyhow::Error;
+
+    fn try_from(condition: String) -> Result<Self> {
+        condition.parse()
+    }
+}
+
+impl From<AlertCondition> for String {
+    fn from(condition: AlertCondition) -> Self {
+        condition.to_string()
+    }
+}
+
+impl fmt::Display for AlertCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.metric.key(),
+            self.comparison.symbol(),
+            self.threshold
+        )?;
+        if !self.duration.is_zero() {
+            write!(f, " for {}s", self.duration.as_secs_f32())?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_duration(text: &str) -> Result<Duration> {
+    let (number, scale) = match text.char_indices().last() {
+        Some((i, 's')) => (&text[..i], 1.0),
+        Some((i, 'm')) => (&text[..i], 60.0),
+        Some((i, 'h')) => (&text[..i], 3600.0),
+        _ => (text, 1.0),
+    };
+    let secs: f32 = number
+        .parse()
+        .map_err(|_| anyhow!("Invalid duration `{}` in alert rule", text))?;
+    Duration::try_from_secs_f32(secs * scale)
+        .map_err(|_| anyhow!("Invalid duration `{}` in alert rule", text))
+}
+
+/// How much a rule firing matters. Sets the urgency of its desktop
+/// notification and the colour of its banner.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Severity {
+    Info,
+    #[default]
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub name: String,
+    pub condition: AlertCondition,
+    /// How far back past the threshold the value has to go before a firing
+    /// rule resolves, in the metric's own units. Stops a value hovering at
+    /// the threshold from firing over and over.
+    #[serde(default, deserialize_with = "deserialize_non_negative")]
+    pub hysteresis: f32,
+    #[serde(default)]
+    pub severity: Severity,
+    /// Shell command run when the rule fires, and again when it resolves.
+    /// It gets the details in `PERCH_ALERT_*` environment variables.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// The least time between two runs of `command` for this rule, so a
+    /// flapping metric can't start it over and over.
+    #[serde(
+        default = "default_cooldown_secs",
+        deserialize_with = "deserialize_non_negative"
+    )]
+    pub cooldown_secs: f32,
+}
+
+fn default_cooldown_secs() -> f32 {
+    300.0
+}
+
+/// A negative hysteresis would resolve a rule while still past its
+/// threshold, and a negative or NaN cooldown never holds a command back.
+fn deserialize_non_negative<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    let value = f32::deserialize(deserializer)?;
+    if value.is_finite() && value >= 0.0 {
+        Ok(value)
+    } else {
+        Err(serde::de::Error::custom(format!(
+            "expected zero or more, not {}",
+            value
+        )))
+    }
+}
+
+/// Where a rule is in its lifecycle. A rule is `Pending` while the
+/// threshold is crossed but not yet for long enough, and `Resolved` for
+/// the one evaluation after it stops firing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertState {
+    Ok,
+    Pending,
+    Firing,
+    Resolved,
+}
+
+impl fmt::Display for AlertState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AlertState::Ok => "ok",
+            AlertState::Pending => "pending",
+            AlertState::Firing => "firing",
+            AlertState::Resolved => "resolved",
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_conditions() {
+        let condition: AlertCondition = "cpu.total > 90 for 30s".parse().unwrap();
+        assert_eq!(condition.metric, Metric::CpuUsage);
+        assert_eq!(condition.comparison, Comparison::Above);
+        assert_eq!(condition.threshold, 90.0);
+        assert_eq!(condition.duration, Duration::from_secs(30));
+
+        let condition: AlertCondition = "mem.used_pct > 85".parse().unwrap();
+        assert_eq!(condition.metric, Metric::MemoryUsage);
+        assert_eq!(condition.duration, Duration::ZERO);
+
+        let condition: AlertCondition = "battery.pct < 10 for 2m".parse().unwrap();
+        assert_eq!(condition.comparison, Comparison::Below);
+        assert_eq!(condition.duration, Duration::from_secs(120));
+    }
+
+    #[test]
+    fn test_rejects_bad_conditions() {
+        for condition in [
+            "",
+            "cpu.total > 90 30s",
+            "cpu.bogus > 90",
+            "cpu.total >= 90",
+            "cpu.total > lots",
+            "cpu.total > nan",
+            "cpu.total > inf",
+            "cpu.total < -inf",
+            "cpu.total > 90 for soon",
+            "cpu.total > 90 for -5s",
+        ] {
+            assert!(
+                condition.parse::<AlertCondition>().is_err(),
+                "{}",
+                condition
+            );
+        }
+    }
+
+    #[test]
+    fn test_hysteresis_widens_clear_threshold() {
+        let above: AlertCondition = "cpu.total > 90".parse().unwrap();
+        assert!(above.is_breached(91.0));
+        assert!(!above.is_cleared(88.0, 5.0));
+        assert!(above.is_cleared(85.0, 5.0));
+
+        let below: AlertCondition = "battery.pct < 10".parse().unwrap();
+        assert!(below.is_breached(9.0));
+        assert!(!below.is_cleared(12.0, 5.0));
+        assert!(below.is_cleared(15.0, 5.0));
+    }
+
+    #[test]
+    fn test_rule_round_trips_through_toml() {
+        let rule = AlertRule {
+            name: "hot cpu".to_string(),
+            condition: "cpu.core3 > 95.5 for 1m".parse().unwrap(),
+            hysteresis: 10.0,
+            severity: Severity::Critical,
+            command: Some("ps aux > /tmp/ps.txt".to_string()),
+            cooldown_secs: 60.0,
+        };
+
+        let text = toml::to_string(&rule).unwrap();
+        assert!(text.contains("condition = \"cpu.core3 > 95.5 for 60s\""));
+        assert_eq!(toml::from_str::<AlertRule>(&text).unwrap(), rule);
+
+        let rule: AlertRule =
+            toml::from_str("name = \"mem\"\ncondition = \"mem.used_pct > 85\"").unwrap();
+        assert_eq!(rule.hysteresis, 0.0);
+        assert_eq!(rule.severity, Severity::Warning);
+        assert_eq!(rule.command, None);
+        assert_eq!(rule.cooldown_secs, 300.0);
+        assert!(toml::from_str::<AlertRule>("name = \"x\"\ncondition = \"nope\"").is_err());
+    }
+
+    #[test]
+    fn test_rejects_negative_hysteresis_and_cooldown() {
+        let rule = |field: &str| {
+            let text = format!("name = \"cpu\"\ncondition = \"cpu.total > 90\"\n{}", field);
+            toml::from_str::<AlertRule>(&text)
+        };
+
+        assert_eq!(rule("hysteresis = 5.0").unwrap().hysteresis, 5.0);
+        assert_eq!(rule("cooldown_secs = 0.0").unwrap().cooldown_secs, 0.0);
+        for value in ["-5.0", "nan", "inf"] {
+            assert!(rule(&format!("hysteresis = {}", value)).is_err());
+            assert!(rule(&format!("cooldown_secs = {}", value)).is_err());
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use serde::{Deserialize, Serialize};
+use crate::models::AlertRule;
+use serde::{Deserialize, Deserializer, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct WindowPosition {
@@ -8,10 +9,7 @@ pub struct WindowPosition {
 
 impl Default for WindowPosition {
     fn default() -> Self {
-        Self {
-            x: 100.0,
-            y: 100.0,
-        }
+        Self { x: 100.0, y: 100.0 }
     }
 }
 
@@ -23,24 +21,249 @@ pub enum Theme {
     Catppuccin,
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ProcessSort {
+    #[default]
+    Cpu,
+    Memory,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum UnitSort {
+    #[default]
+    Cpu,
+    Memory,
+    Io,
+}
+
+/// How far back the detail window's charts reach.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TimeRange {
+    OneMinute,
+    #[default]
+    FiveMinutes,
+    OneHour,
+}
+
+impl TimeRange {
+    pub const ALL: [TimeRange; 3] = [
+        TimeRange::OneMinute,
+        TimeRange::FiveMinutes,
+        TimeRange::OneHour,
+    ];
+
+    pub fn duration(&self) -> std::time::Duration {
+        let secs = match self {
+            TimeRange::OneMinute => 60,
+            TimeRange::FiveMinutes => 5 * 60,
+            TimeRange::OneHour => 60 * 60,
+        };
+        std::time::Duration::from_secs(secs)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TimeRange::OneMinute => "1m",
+            TimeRange::FiveMinutes => "5m",
+            TimeRange::OneHour => "1h",
+        }
+    }
+}
+
+/// How a rate is smoothed before it's shown. Smoothing helps most at short
+/// refresh intervals, where a single sample can be very noisy.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Smoothing {
+    #[default]
+    None,
+    /// Exponential moving average; a sample's weight halves every
+    /// `half_life_secs`.
+    Ewma {
+        #[serde(deserialize_with = "deserialize_smoothing_secs")]
+        half_life_secs: f32,
+    },
+    /// Plain mean of the samples from the last `window_secs`.
+    Window {
+        #[serde(deserialize_with = "deserialize_smoothing_secs")]
+        window_secs: f32,
+    },
+}
+
+/// A NaN window never lets samples age out, and a NaN or infinite half-life
+/// leaves the average stuck, so only finite, non-negative spans are taken.
+/// Zero turns smoothing off.
+fn deserialize_smoothing_secs<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    let secs = f32::deserialize(deserializer)?;
+    if secs.is_finite() && secs >= 0.0 {
+        Ok(secs)
+    } else {
+        Err(serde::de::Error::custom(format!(
+            "smoothing time must be zero or more seconds, not {}",
+            secs
+        )))
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateSmoothing {
+    pub network: Smoothing,
+    pub disk: Smoothing,
+}
+
+/// Limits on the commands alert rules run.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommandLimits {
+    /// A command still running after this long is killed. Anything that
+    /// isn't a positive number of seconds, up to a day, is replaced by the
+    /// default when loading.
+    #[serde(deserialize_with = "deserialize_timeout_secs")]
+    pub timeout_secs: f32,
+    /// Commands started while this many are already running are skipped.
+    pub max_concurrent: usize,
+}
+
+impl Default for CommandLimits {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 30.0,
+            max_concurrent: 2,
+        }
+    }
+}
+
+fn deserialize_timeout_secs<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    const MAX_TIMEOUT_SECS: f32 = 24.0 * 3600.0;
+
+    let secs = f32::deserialize(deserializer)?;
+    if secs > 0.0 && secs <= MAX_TIMEOUT_SECS {
+        return Ok(secs);
+    }
+
+    let default = CommandLimits::default().timeout_secs;
+    eprintln!(
+        "Invalid alert command timeout {}s, using {}s",
+        secs, default
+    );
+    Ok(default)
+}
+
+/// Desktop notifications for alert rules that fire or resolve.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    pub enabled: bool,
+    /// Clicking a notification opens the detail window on the alert's
+    /// metric.
+    pub click_to_open: bool,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            click_to_open: true,
+        }
+    }
+}
+
+/// Which sections draw a sparkline of recent samples under their bar.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SparklineConfig {
+    pub cpu: bool,
+    pub memory: bool,
+    pub network: bool,
+    pub disk: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct UserConfig {
     pub window_position: WindowPosition,
     pub transparency: f32,
     pub refresh_interval_secs: f32,
+    /// How far back the metric history used by graphs and alerts reaches.
+    pub history_retention_secs: u64,
     pub always_on_top: bool,
     pub show_cpu_per_core: bool,
+    pub show_network_interfaces: bool,
+    pub sparklines: SparklineConfig,
+    /// How many of the most recent samples a sparkline spans.
+    pub sparkline_samples: usize,
+    pub detail_range: TimeRange,
+    pub include_virtual_interfaces: bool,
+    pub disabled_collectors: Vec<String>,
+    pub rate_smoothing: RateSmoothing,
+    pub show_processes: bool,
+    pub process_count: usize,
+    pub process_sort: ProcessSort,
+    pub show_pressure: bool,
+    pub show_units: bool,
+    /// The cgroup whose children are listed as units, relative to the
+    /// cgroup v2 mount.
+    pub unit_cgroup: String,
+    pub unit_count: usize,
+    pub unit_sort: UnitSort,
+    /// Mount point globs to show. When empty, every local filesystem that
+    /// isn't a pseudo filesystem is shown.
+    pub filesystem_include: Vec<String>,
+    /// Mount point globs to hide; these take precedence over includes.
+    pub filesystem_exclude: Vec<String>,
+    pub alert_rules: Vec<AlertRule>,
+    pub alert_commands: CommandLimits,
+    pub notifications: NotificationConfig,
     pub theme: Theme,
 }
 
+impl UserConfig {
+    /// Shortest refresh interval allowed. Anything faster costs more CPU
+    /// than the widget is worth.
+    pub const MIN_REFRESH_INTERVAL_SECS: f32 = 0.1;
+
+    /// The sampling interval, clamped to something the sampler can run at
+    /// whatever was written in the config file.
+    pub fn refresh_interval(&self) -> std::time::Duration {
+        let secs = if self.refresh_interval_secs.is_finite() {
+            self.refresh_interval_secs
+                .clamp(Self::MIN_REFRESH_INTERVAL_SECS, 3600.0)
+        } else {
+            UserConfig::default().refresh_interval_secs
+        };
+        std::time::Duration::from_secs_f32(secs)
+    }
+}
+
 impl Default for UserConfig {
     fn default() -> Self {
         Self {
             window_position: WindowPosition::default(),
-            transparency: 0.6,
+            transparency: 0.8,
             refresh_interval_secs: 1.0,
+            history_retention_secs: 3600,
             always_on_top: true,
             show_cpu_per_core: false,
+            show_network_interfaces: false,
+            sparklines: SparklineConfig::default(),
+            sparkline_samples: 60,
+            detail_range: TimeRange::default(),
+            include_virtual_interfaces: false,
+            disabled_collectors: Vec::new(),
+            rate_smoothing: RateSmoothing::default(),
+            show_processes: false,
+            process_count: 5,
+            process_sort: ProcessSort::default(),
+            show_pressure: false,
+            show_units: false,
+            unit_cgroup: "system.slice".to_string(),
+            unit_count: 8,
+            unit_sort: UnitSort::default(),
+            filesystem_include: Vec::new(),
+            filesystem_exclude: Vec::new(),
+            alert_rules: Vec::new(),
+            alert_commands: CommandLimits::default(),
+            notifications: NotificationConfig::default(),
             theme: Theme::default(),
         }
     }
@@ -58,6 +281,83 @@ mod tests {
         assert!(config.always_on_top);
     }
 
+    #[test]
+    fn test_rate_smoothing_round_trips_through_toml() {
+        let config = UserConfig {
+            rate_smoothing: RateSmoothing {
+                network: Smoothing::Ewma {
+                    half_life_secs: 2.0,
+                },
+                disk: Smoothing::Window { window_secs: 5.0 },
+            },
+            ..UserConfig::default()
+        };
+
+        let toml = toml::to_string(&config).unwrap();
+        let parsed: UserConfig = toml::from_str(&toml).unwrap();
+
+        assert_eq!(parsed.rate_smoothing, config.rate_smoothing);
+        assert_eq!(
+            UserConfig::default().rate_smoothing.network,
+            Smoothing::None
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_smoothing_times() {
+        let smoothing = |text: &str| toml::from_str::<RateSmoothing>(text);
+
+        for secs in ["nan", "inf", "-1.0"] {
+            assert!(
+                smoothing(&format!(
+                    "network = {{ Ewma = {{ half_life_secs = {} }} }}",
+                    secs
+                ))
+                .is_err()
+            );
+            assert!(
+                smoothing(&format!(
+                    "disk = {{ Window = {{ window_secs = {} }} }}",
+                    secs
+                ))
+                .is_err()
+            );
+        }
+
+        let parsed = smoothing("disk = { Window = { window_secs = 0.0 } }").unwrap();
+        assert_eq!(parsed.disk, Smoothing::Window { window_secs: 0.0 });
+    }
+
+    #[test]
+    fn test_invalid_command_timeout_uses_default() {
+        for timeout in ["-5.0", "0.0", "nan", "inf", "1e30"] {
+            let text = format!("timeout_secs = {}", timeout);
+            let limits: CommandLimits = toml::from_str(&text).unwrap();
+            assert_eq!(limits.timeout_secs, 30.0, "{}", timeout);
+        }
+
+        let limits: CommandLimits = toml::from_str("timeout_secs = 2.5").unwrap();
+        assert_eq!(limits.timeout_secs, 2.5);
+    }
+
+    #[test]
+    fn test_refresh_interval_is_clamped() {
+        let interval = |secs: f32| {
+            UserConfig {
+                refresh_interval_secs: secs,
+                ..UserConfig::default()
+            }
+            .refresh_interval()
+            .as_millis()
+        };
+
+        assert_eq!(interval(2.5), 2500);
+        assert_eq!(interval(0.0), 100);
+        assert_eq!(interval(-1.0), 100);
+        assert_eq!(interval(f32::NAN), 1000);
+        assert_eq!(interval(f32::INFINITY), 1000);
+    }
+
     #[test]
     fn test_window_position_default() {
         let position = WindowPosition::default();
@@ -0,0 +1,431 @@
+use crate::models::{ResourcePressure, SystemMetrics};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, SystemTime};
+
+/// Hard cap on samples kept per metric, whatever the retention, so a very
+/// short refresh interval can't grow the history without bound.
+const MAX_SAMPLES: usize = 86_400;
+
+/// A single number that can be pulled out of every snapshot. This is the
+/// name graphs, alert rules and the history use to refer to a series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Metric {
+    CpuUsage,
+    MemoryUsage,
+    SwapUsage,
+    NetworkReceived,
+    NetworkTransmitted,
+    DiskRead,
+    DiskWrite,
+    LoadOne,
+    Temperature,
+    BatteryCharge,
+    CpuPressure,
+    MemoryPressure,
+    IoPressure,
+    /// Usage of one logical CPU, by its id. Recorded for every core in a
+    /// snapshot, so it isn't part of `ALL`.
+    CoreUsage(usize),
+}
+
+impl Metric {
+    pub const ALL: [Metric; 13] = [
+        Metric::CpuUsage,
+        Metric::MemoryUsage,
+        Metric::SwapUsage,
+        Metric::NetworkReceived,
+        Metric::NetworkTransmitted,
+        Metric::DiskRead,
+        Metric::DiskWrite,
+        Metric::LoadOne,
+        Metric::Temperature,
+        Metric::BatteryCharge,
+        Metric::CpuPressure,
+        Metric::MemoryPressure,
+        Metric::IoPressure,
+    ];
+
+    /// The name rules in the config use for this metric, like `cpu.total`.
+    pub fn key(&self) -> String {
+        let key = match self {
+            Metric::CpuUsage => "cpu.total",
+            Metric::MemoryUsage => "mem.used_pct",
+            Metric::SwapUsage => "swap.used_pct",
+            Metric::NetworkReceived => "net.rx",
+            Metric::NetworkTransmitted => "net.tx",
+            Metric::DiskRead => "disk.read",
+            Metric::DiskWrite => "disk.write",
+            Metric::LoadOne => "load.1m",
+            Metric::Temperature => "temp.max",
+            Metric::BatteryCharge => "battery.pct",
+            Metric::CpuPressure => "psi.cpu",
+            Metric::MemoryPressure => "psi.memory",
+            Metric::IoPressure => "psi.io",
+            Metric::CoreUsage(id) => return format!("cpu.core{}", id),
+        };
+        key.to_string()
+    }
+
+    pub fn from_key(key: &str) -> Option<Metric> {
+        if let Some(id) = key.strip_prefix("cpu.core") {
+            return id.parse().ok().map(Metric::CoreUsage);
+        }
+        Metric::ALL.into_iter().find(|metric| metric.key() == key)
+    }
+
+    /// The metric's value in a snapshot, or `None` when the snapshot has
+    /// nothing for it (no battery, no sensors, no PSI).
+    pub fn value(&self, metrics: &SystemMetrics) -> Option<f32> {
+        let pressure =
+            |resource: Option<&ResourcePressure>| resource.map(|pressure| pressure.some.avg10);
+
+        match self {
+            Metric::CpuUsage => Some(metrics.scoped_cpu_percentage()),
+            Metric::MemoryUsage => Some(metrics.scoped_memory_percentage()),
+            Metric::SwapUsage => {
+                (metrics.memory.swap_total_bytes > 0).then(|| metrics.memory.swap_percentage())
+            }
+            Metric::NetworkReceived => Some(metrics.network.received_rate),
+            Metric::NetworkTransmitted => Some(metrics.network.transmitted_rate),
+            Metric::DiskRead => Some(metrics.disk.read_rate),
+            Metric::DiskWrite => Some(metrics.disk.write_rate),
+            Metric::LoadOne => Some(metrics.load.load_one as f32),
+            Metric::Temperature => metrics.thermal.hottest().map(|s| s.current_celsius),
+            Metric::BatteryCharge => metrics.batteries.first().map(|b| b.charge_percentage),
+            Metric::CpuPressure => pressure(metrics.pressure.cpu.as_ref()),
+            Metric::MemoryPressure => pressure(metrics.pressure.memory.as_ref()),
+            Metric::IoPressure => pressure(metrics.pressure.io.as_ref()),
+            Metric::CoreUsage(id) => metrics
+                .cpu
+                .per_core
+                .iter()
+                .find(|core| core.id == *id)
+                .map(|core| core.usage_percentage),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricStats {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub p95: f32,
+    pub count: usize,
+}
+
+impl MetricStats {
+    fn from_values(values: &[f32]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f32::total_cmp);
+
+        // Nearest-rank percentile: the smallest value with at least 95% of
+        // samples at or below it.
+        let rank = (0.95 * sorted.len() as f32).ceil() as usize;
+
+        Some(Self {
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            mean: sorted.iter().sum::<f32>() / sorted.len() as f32,
+            p95: sorted[rank.clamp(1, sorted.len()) - 1],
+            count: sorted.len(),
+        })
+    }
+}
+
+/// A bounded, time-indexed record of recent snapshots, kept as one series
+/// per `Metric`. Samples older than the retention time are dropped as new
+/// ones arrive. "Now" is always the newest sample, so queries are unaffected
+/// by how long ago it was taken.
+///
+/// Samples are ordered and aged by the monotonic clock, so stepping the wall
+/// clock either way keeps them. Wall-clock times are only worked out when
+/// samples are read, counting back from the newest sample's timestamp.
+#[derive(Debug, Clone)]
+pub struct MetricHistory {
+    retention: Duration,
+    series: HashMap<Metric, VecDeque<(Duration, f32)>>,
+    /// The newest sample's monotonic and wall-clock times.
+    latest: Option<(Duration, SystemTime)>,
+}
+
+impl MetricHistory {
+    pub fn new(retention: Duration) -> Self {
+        Self {
+            retention,
+            series: HashMap::new(),
+            latest: None,
+        }
+    }
+
+    pub fn record(&mut self, metrics: &SystemMetrics) {
+        // The monotonic clock only goes back for snapshots from a different
+        // monitor, whose timeline can't be mixed with this one.
+        if self
+            .latest
+            .is_some_and(|(latest, _)| metrics.monotonic < latest)
+        {
+            self.series.clear();
+        }
+        self.latest = Some((metrics.monotonic, metrics.timestamp));
+
+        let cores = metrics
+            .cpu
+            .per_core
+            .iter()
+            .map(|core| Metric::CoreUsage(core.id));
+        for metric in Metric::ALL.into_iter().chain(cores) {
+            if let Some(value) = metric.value(metrics) {
+                let series = self.series.entry(metric).or_default();
+                series.push_back((metrics.monotonic, value));
+                if series.len() > MAX_SAMPLES {
+                    series.pop_front();
+                }
+            }
+        }
+
+        self.prune();
+    }
+
+    /// Samples of `metric` from the last `window`, oldest first.
+    pub fn samples(&self, metric: Metric, window: Duration) -> Vec<(SystemTime, f32)> {
+        let (Some(series), Some((latest, timestamp))) = (self.series.get(&metric), self.latest)
+        else {
+            return Vec::new();
+        };
+        let start = latest.saturating_sub(window);
+
+        series
+            .iter()
+            .filter(|(time, _)| *time >= start)
+            .map(|&(time, value)| {
+                let wall = timestamp.checked_sub(latest - time);
+                (wall.unwrap_or(SystemTime::UNIX_EPOCH), value)
+            })
+            .collect()
+    }
+
+    /// The last `count` values of `metric`, oldest first.
+    pub fn recent(&self, metric: Metric, count: usize) -> Vec<f32> {
+        let Some(series) = self.series.get(&metric) else {
+            return Vec::new();
+        };
+
+        series
+            .iter()
+            .skip(series.len().saturating_sub(count))
+            .map(|(_, value)| *value)
+            .collect()
+    }
+
+    pub fn stats(&self, metric: Metric, window: Duration) -> Option<MetricStats> {
+        let values: Vec<f32> = self
+            .samples(metric, window)
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect();
+
+        MetricStats::from_values(&values)
+    }
+
+    /// A copy with the last `window` of each series, and at least its last
+    /// `count` samples, for reading from without holding on to the whole
+    /// history.
+    pub fn copy_recent(&self, window: Duration, count: usize) -> MetricHistory {
+        let Some((latest, _)) = self.latest else {
+            return self.clone();
+        };
+        let start = latest.saturating_sub(window);
+
+        let series = self
+            .series
+            .iter()
+            .map(|(metric, series)| {
+                let first = series
+                    .partition_point(|(time, _)| *time < start)
+                    .min(series.len().saturating_sub(count));
+                (*metric, series.range(first..).copied().collect())
+            })
+            .collect();
+
+        MetricHistory {
+            retention: self.retention,
+            series,
+            latest: self.latest,
+        }
+    }
+
+    fn prune(&mut self) {
+        let Some((latest, _)) = self.latest else {
+            return;
+        };
+        let start = latest.saturating_sub(self.retention);
+
+        for series in self.series.values_mut() {
+            while series.front().is_some_and(|(time, _)| *time < start) {
+                series.pop_front();
+            }
+        }
+        // Cores can go offline; don't keep their emptied series around.
+        self.series.retain(|_, series| !series.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CoreKind, CoreMetrics, CpuTimeBreakdown};
+
+    #[test]
+    fn test_stats_over_window() {
+        let mut history = MetricHistory::new(Duration::from_secs(3600));
+        for i in 0..100 {
+            history.record(&SystemMetrics::at(i, (i + 1) as f32));
+        }
+
+        let all = history
+            .stats(Metric::CpuUsage, Duration::from_secs(3600))
+            .unwrap();
+        assert_eq!(all.count, 100);
+        assert_eq!(all.min, 1.0);
+        assert_eq!(all.max, 100.0);
+        assert_eq!(all.mean, 50.5);
+        assert_eq!(all.p95, 95.0);
+
+        let recent = history
+            .stats(Metric::CpuUsage, Duration::from_secs(9))
+            .unwrap();
+        assert_eq!(recent.count, 10);
+        assert_eq!(recent.min, 91.0);
+    }
+
+    #[test]
+    fn test_retention_drops_old_samples() {
+        let mut history = MetricHistory::new(Duration::from_secs(10));
+        for i in 0..30 {
+            history.record(&SystemMetrics::at(i, i as f32));
+        }
+
+        let samples = history.samples(Metric::CpuUsage, Duration::from_secs(3600));
+        assert_eq!(samples.len(), 11);
+        assert_eq!(samples[0].1, 19.0);
+        assert_eq!(history.recent(Metric::CpuUsage, 3), vec![27.0, 28.0, 29.0]);
+        assert_eq!(history.recent(Metric::CpuUsage, 100).len(), 11);
+        assert!(history.recent(Metric::BatteryCharge, 3).is_empty());
+    }
+
+    #[test]
+    fn test_missing_values_are_not_recorded() {
+        let mut history = MetricHistory::new(Duration::from_secs(60));
+        history.record(&SystemMetrics::at(1, 10.0));
+
+        assert!(
+            history
+                .stats(Metric::BatteryCharge, Duration::from_secs(60))
+                .is_none()
+        );
+        assert!(
+            history
+                .stats(Metric::SwapUsage, Duration::from_secs(60))
+                .is_none()
+        );
+        assert!(
+            MetricHistory::new(Duration::from_secs(60))
+                .stats(Metric::CpuUsage, Duration::from_secs(60))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_records_each_core() {
+        let mut history = MetricHistory::new(Duration::from_secs(10));
+        let mut metrics = SystemMetrics::at(1, 50.0);
+        for (id, usage) in [(0, 20.0), (3, 80.0)] {
+            metrics.cpu.per_core.push(CoreMetrics {
+                id,
+                usage_percentage: usage,
+                frequency_mhz: 0,
+                min_frequency_mhz: 0,
+                max_frequency_mhz: 0,
+                package_id: 0,
+                core_id: id as u32,
+                kind: CoreKind::Unknown,
+                breakdown: CpuTimeBreakdown::default(),
+            });
+        }
+        history.record(&metrics);
+
+        assert_eq!(history.recent(Metric::CoreUsage(0), 1), vec![20.0]);
+        assert_eq!(history.recent(Metric::CoreUsage(3), 1), vec![80.0]);
+        assert!(history.recent(Metric::CoreUsage(1), 1).is_empty());
+
+        history.record(&SystemMetrics::at(100, 50.0));
+        assert!(history.recent(Metric::CoreUsage(0), 1).is_empty());
+    }
+
+    #[test]
+    fn test_metric_keys_round_trip() {
+        for metric in Metric::ALL.into_iter().chain([Metric::CoreUsage(12)]) {
+            assert_eq!(Metric::from_key(&metric.key()), Some(metric));
+        }
+        assert_eq!(Metric::from_key("cpu.core"), None);
+        assert_eq!(Metric::from_key("cpu.idle"), None);
+    }
+
+    #[test]
+    fn test_copy_recent_keeps_window_or_count() {
+        let mut history = MetricHistory::new(Duration::from_secs(3600));
+        for i in 0..100 {
+            history.record(&SystemMetrics::at(i, i as f32));
+        }
+
+        let by_window = history.copy_recent(Duration::from_secs(9), 5);
+        assert_eq!(by_window.recent(Metric::CpuUsage, 100).len(), 10);
+        assert_eq!(
+            by_window.stats(Metric::CpuUsage, Duration::from_secs(9)),
+            history.stats(Metric::CpuUsage, Duration::from_secs(9))
+        );
+
+        let by_count = history.copy_recent(Duration::from_secs(9), 30);
+        assert_eq!(by_count.recent(Metric::CpuUsage, 100).len(), 30);
+        assert_eq!(by_count.recent(Metric::CpuUsage, 1), vec![99.0]);
+    }
+
+    #[test]
+    fn test_wall_clock_steps_keep_history() {
+        let mut history = MetricHistory::new(Duration::from_secs(60));
+        let at = |secs: u64, wall_secs: u64, cpu: f32| {
+            let mut metrics = SystemMetrics::at(secs, cpu);
+            metrics.timestamp = SystemTime::UNIX_EPOCH + Duration::from_secs(wall_secs);
+            metrics
+        };
+
+        history.record(&at(100, 100_000, 10.0));
+        // Stepped back a day, then forward two.
+        history.record(&at(101, 13_601, 20.0));
+        history.record(&at(102, 186_402, 30.0));
+
+        let samples = history.samples(Metric::CpuUsage, Duration::from_secs(60));
+        let wall = |secs: u64| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        assert_eq!(
+            samples,
+            vec![
+                (wall(186_400), 10.0),
+                (wall(186_401), 20.0),
+                (wall(186_402), 30.0)
+            ]
+        );
+        assert_eq!(
+            history
+                .stats(Metric::CpuUsage, Duration::from_secs(1))
+                .unwrap()
+                .count,
+            2
+        );
+    }
+}
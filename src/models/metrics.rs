@@ -1,10 +1,161 @@
+use crate::models::{ProcessSort, UnitSort};
 use serde::{Deserialize, Serialize};
-use std::time::SystemTime;
+use std::cmp::Ordering;
+use std::time::{Duration, SystemTime};
+
+/// A state the CPU spends time in other than idle, as counted in
+/// `/proc/stat`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuState {
+    User,
+    Nice,
+    System,
+    Iowait,
+    Irq,
+    Softirq,
+    Steal,
+    Guest,
+}
+
+impl CpuState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CpuState::User => "user",
+            CpuState::Nice => "nice",
+            CpuState::System => "system",
+            CpuState::Iowait => "iowait",
+            CpuState::Irq => "irq",
+            CpuState::Softirq => "softirq",
+            CpuState::Steal => "steal",
+            CpuState::Guest => "guest",
+        }
+    }
+}
+
+/// Percentage of time spent in each CPU state over the last sample.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuTimeBreakdown {
+    pub user: f32,
+    pub nice: f32,
+    pub system: f32,
+    pub idle: f32,
+    pub iowait: f32,
+    pub irq: f32,
+    pub softirq: f32,
+    pub steal: f32,
+    pub guest: f32,
+}
+
+impl CpuTimeBreakdown {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Busy states in stacking order, with the percentage of each. Idle is
+    /// left out since it's the unfilled part of the bar.
+    pub fn busy_states(&self) -> [(CpuState, f32); 8] {
+        [
+            (CpuState::User, self.user),
+            (CpuState::Nice, self.nice),
+            (CpuState::System, self.system),
+            (CpuState::Iowait, self.iowait),
+            (CpuState::Irq, self.irq),
+            (CpuState::Softirq, self.softirq),
+            (CpuState::Steal, self.steal),
+            (CpuState::Guest, self.guest),
+        ]
+    }
+
+    fn average<'a>(breakdowns: impl Iterator<Item = &'a CpuTimeBreakdown>) -> Self {
+        let mut sum = Self::default();
+        let mut count = 0.0;
+
+        for b in breakdowns {
+            sum.user += b.user;
+            sum.nice += b.nice;
+            sum.system += b.system;
+            sum.idle += b.idle;
+            sum.iowait += b.iowait;
+            sum.irq += b.irq;
+            sum.softirq += b.softirq;
+            sum.steal += b.steal;
+            sum.guest += b.guest;
+            count += 1.0;
+        }
+
+        if count == 0.0 {
+            return sum;
+        }
+
+        Self {
+            user: sum.user / count,
+            nice: sum.nice / count,
+            system: sum.system / count,
+            idle: sum.idle / count,
+            iowait: sum.iowait / count,
+            irq: sum.irq / count,
+            softirq: sum.softirq / count,
+            steal: sum.steal / count,
+            guest: sum.guest / count,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CoreKind {
+    Performance,
+    Efficiency,
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoreMetrics {
+    pub id: usize,
+    pub usage_percentage: f32,
+    pub frequency_mhz: u64,
+    pub min_frequency_mhz: u64,
+    pub max_frequency_mhz: u64,
+    pub package_id: u32,
+    pub core_id: u32,
+    pub kind: CoreKind,
+    pub breakdown: CpuTimeBreakdown,
+}
+
+/// The logical CPUs (SMT siblings) that share one physical core.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoreGroup<'a> {
+    pub package_id: u32,
+    pub core_id: u32,
+    pub kind: CoreKind,
+    pub threads: Vec<&'a CoreMetrics>,
+}
+
+impl CoreGroup<'_> {
+    pub fn usage_percentage(&self) -> f32 {
+        if self.threads.is_empty() {
+            return 0.0;
+        }
+        self.threads.iter().map(|t| t.usage_percentage).sum::<f32>() / self.threads.len() as f32
+    }
+
+    pub fn frequency_mhz(&self) -> u64 {
+        self.threads
+            .iter()
+            .map(|t| t.frequency_mhz)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn breakdown(&self) -> CpuTimeBreakdown {
+        CpuTimeBreakdown::average(self.threads.iter().map(|t| &t.breakdown))
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CPUMetrics {
     pub usage_percentage: f32,
-    pub per_core: Vec<f32>,
+    pub per_core: Vec<CoreMetrics>,
+    pub breakdown: CpuTimeBreakdown,
 }
 
 impl CPUMetrics {
@@ -12,12 +163,86 @@ impl CPUMetrics {
         Self {
             usage_percentage: 0.0,
             per_core: Vec::new(),
+            breakdown: CpuTimeBreakdown::default(),
         }
     }
 
+    #[allow(dead_code)]
     pub fn formatted_usage(&self) -> String {
         format!("{:.2}%", self.usage_percentage)
     }
+
+    /// Groups logical CPUs by package and physical core, in the order their
+    /// first thread appears.
+    pub fn core_groups(&self) -> Vec<CoreGroup<'_>> {
+        let mut groups: Vec<CoreGroup<'_>> = Vec::new();
+
+        for core in &self.per_core {
+            match groups
+                .iter_mut()
+                .find(|g| g.package_id == core.package_id && g.core_id == core.core_id)
+            {
+                Some(group) => group.threads.push(core),
+                None => groups.push(CoreGroup {
+                    package_id: core.package_id,
+                    core_id: core.core_id,
+                    kind: core.kind,
+                    threads: vec![core],
+                }),
+            }
+        }
+
+        groups.sort_by_key(|g| g.package_id);
+        groups
+    }
+}
+
+/// Where memory is going, from `/proc/meminfo`. Every field is in bytes and
+/// stays zero on platforms, or kernels, that don't report it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemoryBreakdown {
+    pub free_bytes: u64,
+    pub buffers_bytes: u64,
+    /// Page cache, including `shared_bytes`.
+    pub cached_bytes: u64,
+    pub shared_bytes: u64,
+    pub slab_reclaimable_bytes: u64,
+    pub slab_unreclaimable_bytes: u64,
+    pub dirty_bytes: u64,
+    pub writeback_bytes: u64,
+    pub hugepages_total_bytes: u64,
+    pub hugepages_free_bytes: u64,
+    pub zswap_compressed_bytes: u64,
+    pub zswap_original_bytes: u64,
+    pub zram_compressed_bytes: u64,
+    pub zram_original_bytes: u64,
+}
+
+impl MemoryBreakdown {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn slab_bytes(&self) -> u64 {
+        self.slab_reclaimable_bytes + self.slab_unreclaimable_bytes
+    }
+
+    /// Cache the kernel can drop under pressure: the page cache minus shared
+    /// memory (which can't simply be dropped) plus reclaimable slab.
+    pub fn cache_bytes(&self) -> u64 {
+        self.cached_bytes.saturating_sub(self.shared_bytes) + self.slab_reclaimable_bytes
+    }
+
+    /// Memory held by processes and the kernel that isn't any kind of cache.
+    /// This matches what `htop` shows as used, so with buffers, shared, cache
+    /// and free it adds up to the total.
+    pub fn application_bytes(&self, total_bytes: u64) -> u64 {
+        total_bytes
+            .saturating_sub(self.free_bytes)
+            .saturating_sub(self.buffers_bytes)
+            .saturating_sub(self.cached_bytes)
+            .saturating_sub(self.slab_reclaimable_bytes)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -27,6 +252,7 @@ pub struct MemoryMetrics {
     pub available_bytes: u64,
     pub swap_total_bytes: u64,
     pub swap_used_bytes: u64,
+    pub breakdown: MemoryBreakdown,
 }
 
 impl MemoryMetrics {
@@ -37,6 +263,7 @@ impl MemoryMetrics {
             available_bytes: 0,
             swap_total_bytes: 0,
             swap_used_bytes: 0,
+            breakdown: MemoryBreakdown::default(),
         }
     }
 
@@ -55,12 +282,31 @@ impl MemoryMetrics {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InterfaceMetrics {
+    pub name: String,
+    pub received_bytes: u64,
+    pub transmitted_bytes: u64,
+    /// Rates as shown, after any smoothing from `UserConfig::rate_smoothing`.
+    pub received_rate: f32,
+    pub transmitted_rate: f32,
+    pub raw_received_rate: f32,
+    pub raw_transmitted_rate: f32,
+    pub is_up: bool,
+    pub is_virtual: bool,
+    pub mac_address: String,
+    pub ip_addresses: Vec<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NetworkMetrics {
     pub received_bytes: u64,
     pub transmitted_bytes: u64,
     pub received_rate: f32,
     pub transmitted_rate: f32,
+    pub raw_received_rate: f32,
+    pub raw_transmitted_rate: f32,
+    pub interfaces: Vec<InterfaceMetrics>,
 }
 
 impl NetworkMetrics {
@@ -70,8 +316,45 @@ impl NetworkMetrics {
             transmitted_bytes: 0,
             received_rate: 0.0,
             transmitted_rate: 0.0,
+            raw_received_rate: 0.0,
+            raw_transmitted_rate: 0.0,
+            interfaces: Vec::new(),
         }
     }
+
+    /// Builds the headline totals from the per-interface list. Loopback and
+    /// other virtual interfaces are only counted when `include_virtual` is set,
+    /// since their traffic is usually already seen on a physical interface.
+    pub fn from_interfaces(interfaces: Vec<InterfaceMetrics>, include_virtual: bool) -> Self {
+        let mut metrics = Self::zero();
+
+        for interface in interfaces
+            .iter()
+            .filter(|i| include_virtual || !i.is_virtual)
+        {
+            metrics.received_bytes += interface.received_bytes;
+            metrics.transmitted_bytes += interface.transmitted_bytes;
+            metrics.received_rate += interface.received_rate;
+            metrics.transmitted_rate += interface.transmitted_rate;
+            metrics.raw_received_rate += interface.raw_received_rate;
+            metrics.raw_transmitted_rate += interface.raw_transmitted_rate;
+        }
+
+        metrics.interfaces = interfaces;
+        metrics
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiskDeviceMetrics {
+    pub name: String,
+    pub read_bytes: u64,
+    pub written_bytes: u64,
+    /// Rates as shown, after any smoothing from `UserConfig::rate_smoothing`.
+    pub read_rate: f32,
+    pub write_rate: f32,
+    pub raw_read_rate: f32,
+    pub raw_write_rate: f32,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -80,6 +363,9 @@ pub struct DiskMetrics {
     pub written_bytes: u64,
     pub read_rate: f32,
     pub write_rate: f32,
+    pub raw_read_rate: f32,
+    pub raw_write_rate: f32,
+    pub devices: Vec<DiskDeviceMetrics>,
 }
 
 impl DiskMetrics {
@@ -89,6 +375,261 @@ impl DiskMetrics {
             written_bytes: 0,
             read_rate: 0.0,
             write_rate: 0.0,
+            raw_read_rate: 0.0,
+            raw_write_rate: 0.0,
+            devices: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilesystemMetrics {
+    pub mount_point: String,
+    pub device: String,
+    pub fs_type: String,
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub available_bytes: u64,
+    pub total_inodes: u64,
+    pub used_inodes: u64,
+}
+
+impl FilesystemMetrics {
+    /// Matches `df`: blocks reserved for root count as neither used nor
+    /// available, so a full disk reads 100% even with reserved space left.
+    pub fn usage_percentage(&self) -> f32 {
+        let usable = self.used_bytes + self.available_bytes;
+        if usable == 0 {
+            return 0.0;
+        }
+        (self.used_bytes as f32 / usable as f32) * 100.0
+    }
+
+    pub fn inode_percentage(&self) -> f32 {
+        if self.total_inodes == 0 {
+            return 0.0;
+        }
+        (self.used_inodes as f32 / self.total_inodes as f32) * 100.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemperatureSensor {
+    pub label: String,
+    pub current_celsius: f32,
+    pub max_celsius: Option<f32>,
+    pub critical_celsius: Option<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FanSensor {
+    pub label: String,
+    pub rpm: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThermalMetrics {
+    pub sensors: Vec<TemperatureSensor>,
+    pub fans: Vec<FanSensor>,
+}
+
+impl ThermalMetrics {
+    pub fn zero() -> Self {
+        Self {
+            sensors: Vec::new(),
+            fans: Vec::new(),
+        }
+    }
+
+    pub fn hottest(&self) -> Option<&TemperatureSensor> {
+        self.sensors
+            .iter()
+            .max_by(|a, b| a.current_celsius.total_cmp(&b.current_celsius))
+    }
+}
+
+/// Usage and limits of the cgroup perch itself runs in. Only present when
+/// that cgroup sets a memory or CPU limit, i.e. inside a container or a
+/// resource-controlled systemd unit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CgroupMetrics {
+    pub path: String,
+    pub memory_current_bytes: u64,
+    pub memory_limit_bytes: Option<u64>,
+    pub cpu_limit_cores: Option<f32>,
+    /// Share of the CPU quota used; with no quota this is relative to every
+    /// host core, like `CPUMetrics::usage_percentage`.
+    pub cpu_usage_percentage: f32,
+    /// Share of the memory limit used, when there is one.
+    pub memory_usage_percentage: Option<f32>,
+}
+
+/// One `some` or `full` line from a `/proc/pressure` file. The averages are
+/// the percentage of wall time spent stalled over 10s, 60s and 300s.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PressureStats {
+    pub avg10: f32,
+    pub avg60: f32,
+    pub avg300: f32,
+    pub total: Duration,
+}
+
+/// `some` is the share of time at least one task was stalled on the
+/// resource, `full` the share where every non-idle task was. CPU only has a
+/// `full` line on kernels 5.13 and later.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourcePressure {
+    pub some: PressureStats,
+    pub full: Option<PressureStats>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PressureMetrics {
+    pub cpu: Option<ResourcePressure>,
+    pub memory: Option<ResourcePressure>,
+    pub io: Option<ResourcePressure>,
+}
+
+impl PressureMetrics {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn is_available(&self) -> bool {
+        self.cpu.is_some() || self.memory.is_some() || self.io.is_some()
+    }
+
+    pub fn resources(&self) -> [(&'static str, Option<&ResourcePressure>); 3] {
+        [
+            ("cpu", self.cpu.as_ref()),
+            ("memory", self.memory.as_ref()),
+            ("io", self.io.as_ref()),
+        ]
+    }
+
+    /// The highest 10 second `some` average across all resources.
+    pub fn worst_avg10(&self) -> f32 {
+        self.resources()
+            .iter()
+            .filter_map(|(_, pressure)| pressure.map(|p| p.some.avg10))
+            .fold(0.0, f32::max)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BatteryState {
+    Charging,
+    Discharging,
+    Full,
+    NotCharging,
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatteryMetrics {
+    pub name: String,
+    pub charge_percentage: f32,
+    pub state: BatteryState,
+    pub power_watts: Option<f32>,
+    pub time_to_full: Option<Duration>,
+    pub time_to_empty: Option<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessMetrics {
+    pub pid: u32,
+    pub name: String,
+    pub command: String,
+    /// Percentage of a single core, as `top` reports it, so busy
+    /// multi-threaded processes can go above 100%.
+    pub cpu_percentage: f32,
+    pub rss_bytes: u64,
+    pub user: String,
+}
+
+impl ProcessMetrics {
+    pub fn compare(&self, other: &Self, sort: ProcessSort) -> Ordering {
+        match sort {
+            ProcessSort::Cpu => other
+                .cpu_percentage
+                .total_cmp(&self.cpu_percentage)
+                .then(other.rss_bytes.cmp(&self.rss_bytes)),
+            ProcessSort::Memory => other
+                .rss_bytes
+                .cmp(&self.rss_bytes)
+                .then(other.cpu_percentage.total_cmp(&self.cpu_percentage)),
+        }
+    }
+}
+
+/// Returns the `count` heaviest processes by the given key, heaviest first.
+pub fn top_processes(
+    processes: &[ProcessMetrics],
+    sort: ProcessSort,
+    count: usize,
+) -> Vec<&ProcessMetrics> {
+    let mut sorted: Vec<&ProcessMetrics> = processes.iter().collect();
+    sorted.sort_by(|a, b| a.compare(b, sort));
+    sorted.truncate(count);
+    sorted
+}
+
+/// Resource use of one child cgroup, typically a systemd service or slice.
+/// Each figure includes everything nested below it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnitMetrics {
+    pub name: String,
+    /// Percentage of a single core, matching `ProcessMetrics`.
+    pub cpu_percentage: f32,
+    pub memory_bytes: u64,
+    pub io_read_rate: f32,
+    pub io_write_rate: f32,
+}
+
+impl UnitMetrics {
+    pub fn io_rate(&self) -> f32 {
+        self.io_read_rate + self.io_write_rate
+    }
+
+    pub fn compare(&self, other: &Self, sort: UnitSort) -> Ordering {
+        let by_cpu = other.cpu_percentage.total_cmp(&self.cpu_percentage);
+        let by_memory = other.memory_bytes.cmp(&self.memory_bytes);
+        let by_io = other.io_rate().total_cmp(&self.io_rate());
+
+        match sort {
+            UnitSort::Cpu => by_cpu.then(by_memory),
+            UnitSort::Memory => by_memory.then(by_cpu),
+            UnitSort::Io => by_io.then(by_cpu),
+        }
+        .then_with(|| self.name.cmp(&other.name))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoadMetrics {
+    pub load_one: f64,
+    pub load_five: f64,
+    pub load_fifteen: f64,
+    pub uptime: Duration,
+    pub boot_time: SystemTime,
+    pub process_count: u32,
+    pub thread_count: u32,
+    pub running_count: u32,
+    pub zombie_count: u32,
+}
+
+impl LoadMetrics {
+    pub fn zero() -> Self {
+        Self {
+            load_one: 0.0,
+            load_five: 0.0,
+            load_fifteen: 0.0,
+            uptime: Duration::ZERO,
+            boot_time: SystemTime::UNIX_EPOCH,
+            process_count: 0,
+            thread_count: 0,
+            running_count: 0,
+            zombie_count: 0,
         }
     }
 }
@@ -99,17 +640,72 @@ pub struct SystemMetrics {
     pub memory: MemoryMetrics,
     pub network: NetworkMetrics,
     pub disk: DiskMetrics,
+    pub filesystems: Vec<FilesystemMetrics>,
+    pub load: LoadMetrics,
+    pub processes: Vec<ProcessMetrics>,
+    pub units: Vec<UnitMetrics>,
+    pub thermal: ThermalMetrics,
+    pub batteries: Vec<BatteryMetrics>,
+    pub pressure: PressureMetrics,
+    pub cgroup: Option<CgroupMetrics>,
     pub timestamp: SystemTime,
+    /// When the sample was taken by the monitor's monotonic clock. Unlike
+    /// `timestamp` it never jumps, so it's what durations are timed with.
+    pub monotonic: Duration,
+    /// Set when the machine was suspended since the previous sample, so
+    /// anything averaged over time should restart rather than bridge it.
+    pub gap_after_suspend: bool,
+    pub sample_cpu_time: Duration,
 }
 
 impl SystemMetrics {
+    /// CPU usage against the cgroup's quota when perch runs under one,
+    /// otherwise across the host. This is what the widget shows.
+    pub fn scoped_cpu_percentage(&self) -> f32 {
+        match &self.cgroup {
+            Some(cgroup) if cgroup.cpu_limit_cores.is_some() => cgroup.cpu_usage_percentage,
+            _ => self.cpu.usage_percentage,
+        }
+    }
+
+    /// Memory usage against the cgroup's limit when perch runs under one,
+    /// otherwise against the host's memory.
+    pub fn scoped_memory_percentage(&self) -> f32 {
+        self.cgroup
+            .as_ref()
+            .and_then(|cgroup| cgroup.memory_usage_percentage)
+            .unwrap_or_else(|| self.memory.usage_percentage())
+    }
+
+    /// A snapshot taken `secs` seconds after the epoch on both clocks, with
+    /// only CPU usage filled in. For tests that step through time.
+    #[cfg(test)]
+    pub fn at(secs: u64, cpu_percentage: f32) -> Self {
+        let mut metrics = Self::zero();
+        metrics.timestamp = SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        metrics.monotonic = Duration::from_secs(secs);
+        metrics.cpu.usage_percentage = cpu_percentage;
+        metrics
+    }
+
     pub fn zero() -> Self {
         Self {
             cpu: CPUMetrics::zero(),
             memory: MemoryMetrics::zero(),
             network: NetworkMetrics::zero(),
             disk: DiskMetrics::zero(),
+            filesystems: Vec::new(),
+            load: LoadMetrics::zero(),
+            processes: Vec::new(),
+            units: Vec::new(),
+            thermal: ThermalMetrics::zero(),
+            batteries: Vec::new(),
+            pressure: PressureMetrics::zero(),
+            cgroup: None,
             timestamp: SystemTime::now(),
+            monotonic: Duration::ZERO,
+            gap_after_suspend: false,
+            sample_cpu_time: Duration::ZERO,
         }
     }
 }
@@ -123,10 +719,54 @@ mod tests {
         let metrics = CPUMetrics {
             usage_percentage: 45.678,
             per_core: vec![],
+            breakdown: CpuTimeBreakdown::default(),
         };
         assert_eq!(metrics.formatted_usage(), "45.68%");
     }
 
+    fn core(id: usize, core_id: u32, usage_percentage: f32) -> CoreMetrics {
+        CoreMetrics {
+            id,
+            usage_percentage,
+            frequency_mhz: 1000 + id as u64,
+            min_frequency_mhz: 800,
+            max_frequency_mhz: 4000,
+            package_id: 0,
+            core_id,
+            kind: CoreKind::Unknown,
+            breakdown: CpuTimeBreakdown {
+                user: usage_percentage,
+                idle: 100.0 - usage_percentage,
+                ..CpuTimeBreakdown::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_cpu_metrics_core_groups_merge_smt_siblings() {
+        let metrics = CPUMetrics {
+            usage_percentage: 0.0,
+            per_core: vec![
+                core(0, 0, 10.0),
+                core(1, 1, 40.0),
+                core(2, 0, 30.0),
+                core(3, 1, 0.0),
+            ],
+            breakdown: CpuTimeBreakdown::default(),
+        };
+
+        let groups = metrics.core_groups();
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].core_id, 0);
+        assert_eq!(groups[0].threads.len(), 2);
+        assert_eq!(groups[0].usage_percentage(), 20.0);
+        assert_eq!(groups[0].frequency_mhz(), 1002);
+        assert_eq!(groups[1].usage_percentage(), 20.0);
+        assert_eq!(groups[0].breakdown().user, 20.0);
+        assert_eq!(groups[0].breakdown().idle, 80.0);
+    }
+
     #[test]
     fn test_memory_metrics_usage_percentage_calculates_correctly() {
         let metrics = MemoryMetrics {
@@ -135,6 +775,7 @@ mod tests {
             available_bytes: 250,
             swap_total_bytes: 0,
             swap_used_bytes: 0,
+            breakdown: MemoryBreakdown::default(),
         };
         assert_eq!(metrics.usage_percentage(), 75.0);
     }
@@ -147,6 +788,7 @@ mod tests {
             available_bytes: 0,
             swap_total_bytes: 2000,
             swap_used_bytes: 500,
+            breakdown: MemoryBreakdown::default(),
         };
         assert_eq!(metrics.swap_percentage(), 25.0);
     }
@@ -159,8 +801,206 @@ mod tests {
             available_bytes: 0,
             swap_total_bytes: 0,
             swap_used_bytes: 0,
+            breakdown: MemoryBreakdown::default(),
         };
         assert_eq!(metrics.usage_percentage(), 0.0);
         assert_eq!(metrics.swap_percentage(), 0.0);
     }
+
+    #[test]
+    fn test_memory_breakdown_segments_add_up_to_total() {
+        let breakdown = MemoryBreakdown {
+            free_bytes: 200,
+            buffers_bytes: 50,
+            cached_bytes: 300,
+            shared_bytes: 40,
+            slab_reclaimable_bytes: 60,
+            slab_unreclaimable_bytes: 20,
+            ..MemoryBreakdown::default()
+        };
+
+        assert_eq!(breakdown.slab_bytes(), 80);
+        assert_eq!(breakdown.cache_bytes(), 320);
+        assert_eq!(breakdown.application_bytes(1000), 390);
+        assert_eq!(
+            breakdown.application_bytes(1000)
+                + breakdown.buffers_bytes
+                + breakdown.shared_bytes
+                + breakdown.cache_bytes()
+                + breakdown.free_bytes,
+            1000
+        );
+        assert!(MemoryBreakdown::default().is_empty());
+    }
+
+    #[test]
+    fn test_scoped_usage_follows_cgroup_limits() {
+        let mut metrics = SystemMetrics::zero();
+        metrics.cpu.usage_percentage = 10.0;
+        metrics.memory.used_bytes = 500;
+        metrics.memory.total_bytes = 1000;
+        assert_eq!(metrics.scoped_cpu_percentage(), 10.0);
+        assert_eq!(metrics.scoped_memory_percentage(), 50.0);
+
+        metrics.cgroup = Some(CgroupMetrics {
+            path: "/system.slice/perch.service".to_string(),
+            memory_current_bytes: 256,
+            memory_limit_bytes: Some(1024),
+            cpu_limit_cores: None,
+            cpu_usage_percentage: 40.0,
+            memory_usage_percentage: Some(25.0),
+        });
+        // Without a quota the cgroup's CPU share is no more than the host's.
+        assert_eq!(metrics.scoped_cpu_percentage(), 10.0);
+        assert_eq!(metrics.scoped_memory_percentage(), 25.0);
+
+        metrics.cgroup.as_mut().unwrap().cpu_limit_cores = Some(0.5);
+        assert_eq!(metrics.scoped_cpu_percentage(), 40.0);
+    }
+
+    #[test]
+    fn test_unit_compare_by_each_key() {
+        let unit = |name: &str, cpu_percentage: f32, memory_bytes: u64, io: f32| UnitMetrics {
+            name: name.to_string(),
+            cpu_percentage,
+            memory_bytes,
+            io_read_rate: io,
+            io_write_rate: 0.0,
+        };
+        let mut units = vec![
+            unit("a.service", 5.0, 300, 0.0),
+            unit("b.service", 50.0, 100, 10.0),
+            unit("c.service", 1.0, 200, 900.0),
+        ];
+
+        let names = |units: &[UnitMetrics]| -> Vec<String> {
+            units.iter().map(|u| u.name.clone()).collect()
+        };
+
+        units.sort_by(|a, b| a.compare(b, UnitSort::Cpu));
+        assert_eq!(names(&units), ["b.service", "a.service", "c.service"]);
+        units.sort_by(|a, b| a.compare(b, UnitSort::Memory));
+        assert_eq!(names(&units), ["a.service", "c.service", "b.service"]);
+        units.sort_by(|a, b| a.compare(b, UnitSort::Io));
+        assert_eq!(names(&units), ["c.service", "b.service", "a.service"]);
+    }
+
+    fn interface(name: &str, is_virtual: bool, received: u64, rate: f32) -> InterfaceMetrics {
+        InterfaceMetrics {
+            name: name.to_string(),
+            received_bytes: received,
+            transmitted_bytes: received / 2,
+            received_rate: rate,
+            transmitted_rate: rate / 2.0,
+            raw_received_rate: rate * 2.0,
+            raw_transmitted_rate: rate,
+            is_up: true,
+            is_virtual,
+            mac_address: String::new(),
+            ip_addresses: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_network_metrics_from_interfaces_excludes_virtual() {
+        let interfaces = vec![
+            interface("eth0", false, 1000, 100.0),
+            interface("wlan0", false, 500, 50.0),
+            interface("lo", true, 9000, 900.0),
+        ];
+
+        let metrics = NetworkMetrics::from_interfaces(interfaces, false);
+
+        assert_eq!(metrics.received_bytes, 1500);
+        assert_eq!(metrics.transmitted_bytes, 750);
+        assert_eq!(metrics.received_rate, 150.0);
+        assert_eq!(metrics.raw_received_rate, 300.0);
+        assert_eq!(metrics.interfaces.len(), 3);
+    }
+
+    #[test]
+    fn test_network_metrics_from_interfaces_can_include_virtual() {
+        let interfaces = vec![
+            interface("eth0", false, 1000, 100.0),
+            interface("lo", true, 9000, 900.0),
+        ];
+
+        let metrics = NetworkMetrics::from_interfaces(interfaces, true);
+
+        assert_eq!(metrics.received_bytes, 10000);
+        assert_eq!(metrics.received_rate, 1000.0);
+    }
+
+    #[test]
+    fn test_filesystem_metrics_percentages() {
+        let metrics = FilesystemMetrics {
+            mount_point: "/".to_string(),
+            device: "/dev/sda1".to_string(),
+            fs_type: "ext4".to_string(),
+            total_bytes: 1000,
+            used_bytes: 600,
+            available_bytes: 200,
+            total_inodes: 100,
+            used_inodes: 25,
+        };
+
+        assert_eq!(metrics.usage_percentage(), 75.0);
+        assert_eq!(metrics.inode_percentage(), 25.0);
+    }
+
+    #[test]
+    fn test_thermal_metrics_hottest() {
+        let sensor = |label: &str, current_celsius: f32| TemperatureSensor {
+            label: label.to_string(),
+            current_celsius,
+            max_celsius: None,
+            critical_celsius: None,
+        };
+        let thermal = ThermalMetrics {
+            sensors: vec![sensor("a", 40.0), sensor("b", 71.5), sensor("c", 55.0)],
+            fans: Vec::new(),
+        };
+
+        assert_eq!(thermal.hottest().unwrap().label, "b");
+        assert!(ThermalMetrics::zero().hottest().is_none());
+    }
+
+    fn process(pid: u32, cpu_percentage: f32, rss_bytes: u64) -> ProcessMetrics {
+        ProcessMetrics {
+            pid,
+            name: format!("proc{}", pid),
+            command: String::new(),
+            cpu_percentage,
+            rss_bytes,
+            user: "root".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_top_processes_sorts_by_cpu() {
+        let processes = vec![
+            process(1, 5.0, 300),
+            process(2, 50.0, 100),
+            process(3, 20.0, 200),
+        ];
+
+        let top = top_processes(&processes, ProcessSort::Cpu, 2);
+        let pids: Vec<u32> = top.iter().map(|p| p.pid).collect();
+
+        assert_eq!(pids, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_top_processes_sorts_by_memory() {
+        let processes = vec![
+            process(1, 5.0, 300),
+            process(2, 50.0, 100),
+            process(3, 20.0, 200),
+        ];
+
+        let top = top_processes(&processes, ProcessSort::Memory, 5);
+        let pids: Vec<u32> = top.iter().map(|p| p.pid).collect();
+
+        assert_eq!(pids, vec![1, 3, 2]);
+    }
 }
@@ -1,5 +1,15 @@
+pub mod alert;
 pub mod config;
+pub mod history;
 pub mod metrics;
 
-pub use config::{Theme, UserConfig};
-pub use metrics::{CPUMetrics, DiskMetrics, MemoryMetrics, NetworkMetrics, SystemMetrics};
+pub use alert::{AlertRule, AlertState, Severity};
+pub use config::{CommandLimits, ProcessSort, Smoothing, Theme, TimeRange, UnitSort, UserConfig};
+pub use history::{Metric, MetricHistory};
+pub use metrics::{
+    BatteryMetrics, BatteryState, CPUMetrics, CgroupMetrics, CoreKind, CoreMetrics, CpuState,
+    CpuTimeBreakdown, DiskDeviceMetrics, DiskMetrics, FanSensor, FilesystemMetrics,
+    InterfaceMetrics, LoadMetrics, MemoryBreakdown, MemoryMetrics, NetworkMetrics, PressureMetrics,
+    PressureStats, ProcessMetrics, ResourcePressure, SystemMetrics, TemperatureSensor,
+    ThermalMetrics, UnitMetrics, top_processes,
+};
@@ -0,0 +1,345 @@
+use crate::models::{AlertState, CommandLimits};
+use crate::services::alerts::AlertEvent;
+use std::collections::{HashMap, HashSet};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How often a running command is checked for having exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Runs the `command` of an alert rule when the rule fires or resolves.
+/// Each run is watched on its own thread and killed if it outlives the
+/// timeout. A rule's firing command won't start again within its cooldown,
+/// and its resolve command only runs if the firing one did, so the two
+/// always come in pairs.
+pub struct CommandRunner {
+    limits: CommandLimits,
+    running: Arc<AtomicUsize>,
+    /// When each rule's firing command last ran, on the monotonic clock so
+    /// that wall clock changes can't cut a cooldown short.
+    last_fired: HashMap<String, Duration>,
+    awaiting_resolve: HashSet<String>,
+}
+
+impl CommandRunner {
+    pub fn new(limits: CommandLimits) -> Self {
+        Self {
+            limits,
+            running: Arc::new(AtomicUsize::new(0)),
+            last_fired: HashMap::new(),
+            awaiting_resolve: HashSet::new(),
+        }
+    }
+
+    /// Starts the command for `event`, if its rule has one and the limits
+    /// allow it. The returned handle yields the exit status, or `None` if
+    /// the command timed out or couldn't be waited on.
+    pub fn run(&mut self, event: &AlertEvent) -> Option<JoinHandle<Option<ExitStatus>>> {
+        let rule = &event.rule;
+        let command = rule.command.as_deref()?;
+
+        match event.state {
+            AlertState::Firing => {
+                let cooling_down = self.last_fired.get(&rule.name).is_some_and(|last| {
+                    let since = event.monotonic.saturating_sub(*last);
+                    since.as_secs_f32() < rule.cooldown_secs
+                });
+                if cooling_down {
+                    eprintln!("Skipping command for alert `{}`: cooling down", rule.name);
+                    return None;
+                }
+            }
+            // Whether or not the resolve command gets to run, this cycle is
+            // over, so a later resolve can't pair with its firing.
+            AlertState::Resolved => {
+                if !self.awaiting_resolve.remove(&rule.name) {
+                    return None;
+                }
+            }
+            AlertState::Ok | AlertState::Pending => return None,
+        }
+
+        let running = self.running.load(Ordering::SeqCst);
+        if running >= self.limits.max_concurrent {
+            eprintln!(
+                "Skipping command for alert `{}`: {} commands already running",
+                rule.name, running
+            );
+            return None;
+        }
+
+        let child = match spawn(command, event) {
+            Ok(child) => child,
+            Err(e) => {
+                eprintln!("Failed to run command for alert `{}`: {}", rule.name, e);
+                return None;
+            }
+        };
+
+        if event.state == AlertState::Firing {
+            self.last_fired.insert(rule.name.clone(), event.monotonic);
+            self.awaiting_resolve.insert(rule.name.clone());
+        }
+
+        self.running.fetch_add(1, Ordering::SeqCst);
+        let running = Arc::clone(&self.running);
+        let timeout = Duration::try_from_secs_f32(self.limits.timeout_secs).unwrap_or_default();
+        let name = rule.name.clone();
+
+        let handle = thread::Builder::new()
+            .name("perch-alert-command".to_string())
+            .spawn(move || {
+                let status = supervise(child, timeout, &name);
+                running.fetch_sub(1, Ordering::SeqCst);
+                status
+            });
+
+        match handle {
+            Ok(handle) => Some(handle),
+            Err(e) => {
+                eprintln!("Failed to watch command for alert `{}`: {}", rule.name, e);
+                self.running.fetch_sub(1, Ordering::SeqCst);
+                None
+            }
+        }
+    }
+}
+
+fn spawn(command: &str, event: &AlertEvent) -> std::io::Result<Child> {
+    let condition = event.rule.condition;
+
+    let mut process = shell(command);
+    process
+        .env("PERCH_ALERT_NAME", &event.rule.name)
+        .env("PERCH_ALERT_STATE", event.state.to_string())
+        .env("PERCH_ALERT_METRIC", condition.metric.key())
+        .env("PERCH_ALERT_VALUE", event.value.to_string())
+        .env("PERCH_ALERT_THRESHOLD", condition.threshold.to_string())
+        .env("PERCH_ALERT_CONDITION", condition.to_string())
+        .stdin(Stdio::null());
+
+    process.spawn()
+}
+
+/// A shell running `command`, in its own process group on unix so that a
+/// timeout can kill everything it started.
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    use std::os::unix::process::CommandExt;
+
+    let mut process = Command::new("sh");
+    process.arg("-c").arg(command).process_group(0);
+    process
+}
+
+#[cfg(not(unix))]
+fn shell(command: &str) -> Command {
+    let mut process = Command::new("cmd");
+    process.arg("/C").arg(command);
+    process
+}
+
+fn supervise(mut child: Child, timeout: Duration, name: &str) -> Option<ExitStatus> {
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                if !status.success() {
+                    eprintln!("Command for alert `{}` exited with {}", name, status);
+                }
+                return Some(status);
+            }
+            Ok(None) if Instant::now() >= deadline => {
+                eprintln!("Command for alert `{}` timed out after {:?}", name, timeout);
+                kill(&mut child);
+                let _ = child.wait();
+                return None;
+            }
+            Ok(None) => thread::sleep(POLL_INTERVAL),
+            Err(e) => {
+                eprintln!("Failed to wait on command for alert `{}`: {}", name, e);
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn kill(child: &mut Child) {
+    // SAFETY: kill(2) only takes plain integers. The child leads its own
+    // process group, so the negated pid names that whole group.
+    let result = unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) };
+    if result != 0 {
+        let _ = child.kill();
+    }
+}
+
+#[cfg(not(unix))]
+fn kill(child: &mut Child) {
+    let _ = child.kill();
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::models::AlertRule;
+    use crate::models::Severity;
+    use std::fs;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    fn event(command: &str, state: AlertState, secs: u64) -> AlertEvent {
+        AlertEvent {
+            rule: AlertRule {
+                name: "hot cpu".to_string(),
+                condition: "cpu.total > 90 for 30s".parse().unwrap(),
+                hysteresis: 0.0,
+                severity: Severity::default(),
+                command: Some(command.to_string()),
+                cooldown_secs: 60.0,
+            },
+            state,
+            value: 95.5,
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+            monotonic: Duration::from_secs(secs),
+        }
+    }
+
+    fn limits(timeout_secs: f32, max_concurrent: usize) -> CommandLimits {
+        CommandLimits {
+            timeout_secs,
+            max_concurrent,
+        }
+    }
+
+    #[test]
+    fn test_command_gets_alert_environment() {
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("env");
+        let fields = "$PERCH_ALERT_NAME|$PERCH_ALERT_STATE|$PERCH_ALERT_METRIC|$PERCH_ALERT_VALUE";
+        let command = format!("echo \"{}\" > {}", fields, out.display());
+
+        let mut runner = CommandRunner::new(limits(5.0, 2));
+        let status = runner
+            .run(&event(&command, AlertState::Firing, 0))
+            .unwrap()
+            .join()
+            .unwrap();
+
+        assert!(status.unwrap().success());
+        assert_eq!(
+            fs::read_to_string(&out).unwrap(),
+            "hot cpu|firing|cpu.total|95.5\n"
+        );
+    }
+
+    #[test]
+    fn test_timeout_kills_command() {
+        let mut runner = CommandRunner::new(limits(0.2, 2));
+        let started = Instant::now();
+        let status = runner
+            .run(&event("sleep 10", AlertState::Firing, 0))
+            .unwrap()
+            .join()
+            .unwrap();
+
+        assert!(status.is_none());
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(runner.running.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_skips_commands_over_concurrency_limit() {
+        let mut runner = CommandRunner::new(limits(0.5, 1));
+        let first = runner
+            .run(&event("sleep 10", AlertState::Firing, 0))
+            .unwrap();
+
+        let mut other = event("true", AlertState::Firing, 0);
+        other.rule.name = "other".to_string();
+        assert!(runner.run(&other).is_none());
+
+        first.join().unwrap();
+        assert!(runner.run(&other).is_some());
+    }
+
+    #[test]
+    fn test_cooldown_and_resolve_pairing() {
+        let mut runner = CommandRunner::new(limits(5.0, 4));
+
+        // Resolving a rule whose firing command never ran does nothing.
+        assert!(
+            runner
+                .run(&event("true", AlertState::Resolved, 0))
+                .is_none()
+        );
+
+        assert!(runner.run(&event("true", AlertState::Firing, 0)).is_some());
+        assert!(
+            runner
+                .run(&event("true", AlertState::Resolved, 10))
+                .is_some()
+        );
+        assert!(runner.run(&event("true", AlertState::Firing, 30)).is_none());
+        assert!(
+            runner
+                .run(&event("true", AlertState::Resolved, 40))
+                .is_none()
+        );
+        assert!(runner.run(&event("true", AlertState::Firing, 61)).is_some());
+    }
+
+    #[test]
+    fn test_skipped_resolve_ends_the_cycle() {
+        let mut runner = CommandRunner::new(limits(0.5, 1));
+        assert!(
+            runner
+                .run(&event("true", AlertState::Firing, 0))
+                .unwrap()
+                .join()
+                .is_ok()
+        );
+
+        let mut other = event("sleep 10", AlertState::Firing, 0);
+        other.rule.name = "other".to_string();
+        let busy = runner.run(&other).unwrap();
+        assert!(
+            runner
+                .run(&event("true", AlertState::Resolved, 10))
+                .is_none()
+        );
+        busy.join().unwrap();
+
+        // Fires again inside the cooldown, so its command is skipped, and
+        // the resolve that follows has nothing to pair with.
+        assert!(runner.run(&event("true", AlertState::Firing, 20)).is_none());
+        assert!(
+            runner
+                .run(&event("true", AlertState::Resolved, 30))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_wall_clock_going_back_keeps_cooldown() {
+        let mut runner = CommandRunner::new(limits(5.0, 4));
+        assert!(
+            runner
+                .run(&event("true", AlertState::Firing, 100))
+                .is_some()
+        );
+        assert!(
+            runner
+                .run(&event("true", AlertState::Resolved, 110))
+                .is_some()
+        );
+
+        let mut again = event("true", AlertState::Firing, 120);
+        again.timestamp = SystemTime::UNIX_EPOCH;
+        assert!(runner.run(&again).is_none());
+    }
+}
@@ -0,0 +1,284 @@
+use crate::models::{AlertRule, AlertState, SystemMetrics};
+use crate::services::{format_clock_time, format_metric};
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+/// Where one rule currently stands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleStatus {
+    pub rule: AlertRule,
+    pub state: AlertState,
+    /// When the rule entered its current state.
+    pub since: SystemTime,
+    /// The same moment on the monotonic clock, which times `for` durations
+    /// so that wall clock adjustments can't cut them short or stretch them.
+    pub entered: Duration,
+    /// The metric's value at the last evaluation.
+    pub value: Option<f32>,
+}
+
+/// A rule moving into a new state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertEvent {
+    pub rule: AlertRule,
+    pub state: AlertState,
+    pub value: f32,
+    pub timestamp: SystemTime,
+    /// The same moment on the monitor's monotonic clock, for timing
+    /// anything that follows from the event.
+    pub monotonic: Duration,
+}
+
+impl fmt::Display for AlertEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] alert `{}` {}: {} (now {})",
+            format_clock_time(self.timestamp),
+            self.rule.name,
+            self.state,
+            self.rule.condition,
+            format_metric(self.rule.condition.metric, self.value)
+        )
+    }
+}
+
+/// Evaluates the configured alert rules against each new snapshot. A rule
+/// goes Ok → Pending when its threshold is crossed, Pending → Firing once
+/// it has stayed crossed for the rule's duration, and Firing → Resolved
+/// once the value is back past the threshold by the rule's hysteresis.
+pub struct AlertEngine {
+    rules: Vec<RuleStatus>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        Self {
+            rules: rules
+                .into_iter()
+                .map(|rule| RuleStatus {
+                    rule,
+                    state: AlertState::Ok,
+                    since: SystemTime::UNIX_EPOCH,
+                    entered: Duration::ZERO,
+                    value: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Steps every rule on to `metrics` and returns the ones that changed
+    /// state. A rule whose metric is missing from the snapshot stays as it
+    /// is.
+    pub fn evaluate(&mut self, metrics: &SystemMetrics) -> Vec<AlertEvent> {
+        let now = metrics.timestamp;
+        let monotonic = metrics.monotonic;
+        let mut events = Vec::new();
+
+        for status in &mut self.rules {
+            let condition = status.rule.condition;
+            let Some(value) = condition.metric.value(metrics) else {
+                continue;
+            };
+            status.value = Some(value);
+
+            // Time spent suspended says nothing about the metric, so a
+            // pending rule starts counting again.
+            if metrics.gap_after_suspend && status.state == AlertState::Pending {
+                status.since = now;
+                status.entered = monotonic;
+            }
+
+            let breached = condition.is_breached(value);
+            let held_for = monotonic.saturating_sub(status.entered);
+            let next = match status.state {
+                AlertState::Ok | AlertState::Resolved if breached => {
+                    if condition.duration.is_zero() {
+                        AlertState::Firing
+                    } else {
+                        AlertState::Pending
+                    }
+                }
+                AlertState::Ok | AlertState::Resolved => AlertState::Ok,
+                AlertState::Pending if !breached => AlertState::Ok,
+                AlertState::Pending if held_for >= condition.duration => AlertState::Firing,
+                AlertState::Pending => AlertState::Pending,
+                AlertState::Firing if condition.is_cleared(value, status.rule.hysteresis) => {
+                    AlertState::Resolved
+                }
+                AlertState::Firing => AlertState::Firing,
+            };
+
+            if next == status.state {
+                continue;
+            }
+            let previous = std::mem::replace(&mut status.state, next);
+            status.since = now;
+            status.entered = monotonic;
+
+            // Resolved only marks the evaluation a rule stopped firing, so
+            // settling back to Ok afterwards isn't worth reporting.
+            if previous == AlertState::Resolved && next == AlertState::Ok {
+                continue;
+            }
+            events.push(AlertEvent {
+                rule: status.rule.clone(),
+                state: next,
+                value,
+                timestamp: now,
+                monotonic,
+            });
+        }
+
+        events
+    }
+
+    pub fn firing(&self) -> impl Iterator<Item = &RuleStatus> {
+        self.rules
+            .iter()
+            .filter(|status| status.state == AlertState::Firing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Severity;
+
+    fn rule(condition: &str, hysteresis: f32) -> AlertRule {
+        AlertRule {
+            name: "test".to_string(),
+            condition: condition.parse().unwrap(),
+            hysteresis,
+            severity: Severity::default(),
+            command: None,
+            cooldown_secs: 0.0,
+        }
+    }
+
+    fn states(engine: &mut AlertEngine, samples: &[(u64, f32)]) -> Vec<Vec<AlertState>> {
+        samples
+            .iter()
+            .map(|&(secs, cpu)| {
+                engine
+                    .evaluate(&SystemMetrics::at(secs, cpu))
+                    .into_iter()
+                    .map(|event| event.state)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fires_after_duration_and_resolves() {
+        let mut engine = AlertEngine::new(vec![rule("cpu.total > 90 for 30s", 0.0)]);
+        let events = states(
+            &mut engine,
+            &[
+                (0, 50.0),
+                (10, 95.0),
+                (30, 95.0),
+                (40, 95.0),
+                (50, 80.0),
+                (60, 80.0),
+            ],
+        );
+
+        assert_eq!(
+            events,
+            vec![
+                vec![],
+                vec![AlertState::Pending],
+                vec![],
+                vec![AlertState::Firing],
+                vec![AlertState::Resolved],
+                vec![],
+            ]
+        );
+        assert_eq!(engine.firing().count(), 0);
+    }
+
+    #[test]
+    fn test_pending_returns_to_ok_when_threshold_uncrossed() {
+        let mut engine = AlertEngine::new(vec![rule("cpu.total > 90 for 30s", 0.0)]);
+        let events = states(
+            &mut engine,
+            &[(0, 95.0), (10, 50.0), (20, 95.0), (45, 95.0)],
+        );
+
+        assert_eq!(
+            events,
+            vec![
+                vec![AlertState::Pending],
+                vec![AlertState::Ok],
+                vec![AlertState::Pending],
+                vec![]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_zero_duration_fires_immediately() {
+        let mut engine = AlertEngine::new(vec![rule("mem.used_pct < 50", 0.0)]);
+        let events = engine.evaluate(&SystemMetrics::at(0, 0.0));
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].state, AlertState::Firing);
+        assert_eq!(engine.firing().count(), 1);
+    }
+
+    #[test]
+    fn test_hysteresis_keeps_rule_firing() {
+        let mut engine = AlertEngine::new(vec![rule("cpu.total > 90", 10.0)]);
+        let events = states(&mut engine, &[(0, 95.0), (1, 89.0), (2, 85.0), (3, 80.0)]);
+
+        assert_eq!(
+            events,
+            vec![
+                vec![AlertState::Firing],
+                vec![],
+                vec![],
+                vec![AlertState::Resolved]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_suspend_restarts_pending_duration() {
+        let mut engine = AlertEngine::new(vec![rule("cpu.total > 90 for 30s", 0.0)]);
+        engine.evaluate(&SystemMetrics::at(0, 95.0));
+
+        let mut resumed = SystemMetrics::at(3600, 95.0);
+        resumed.gap_after_suspend = true;
+        assert!(engine.evaluate(&resumed).is_empty());
+        assert_eq!(
+            engine.evaluate(&SystemMetrics::at(3630, 95.0))[0].state,
+            AlertState::Firing
+        );
+    }
+
+    #[test]
+    fn test_wall_clock_steps_do_not_change_duration() {
+        let mut engine = AlertEngine::new(vec![rule("cpu.total > 90 for 30s", 0.0)]);
+        engine.evaluate(&SystemMetrics::at(0, 95.0));
+
+        // The wall clock jumps an hour ahead and then back again, while
+        // only ten seconds pass.
+        let mut ahead = SystemMetrics::at(10, 95.0);
+        ahead.timestamp += Duration::from_secs(3600);
+        assert!(engine.evaluate(&ahead).is_empty());
+
+        let mut behind = SystemMetrics::at(30, 95.0);
+        behind.timestamp -= Duration::from_secs(600);
+        assert_eq!(engine.evaluate(&behind)[0].state, AlertState::Firing);
+    }
+
+    #[test]
+    fn test_missing_metric_leaves_rule_alone() {
+        let mut engine = AlertEngine::new(vec![rule("battery.pct < 10", 0.0)]);
+
+        assert!(engine.evaluate(&SystemMetrics::at(0, 0.0)).is_empty());
+        assert_eq!(engine.rules[0].state, AlertState::Ok);
+        assert_eq!(engine.rules[0].value, None);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::services::sysfs::{read_parsed, read_trimmed};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// A raw reading of one cgroup v2 directory. CPU usage is cumulative, so
+/// callers diff successive readings to get a rate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CgroupStats {
+    pub path: String,
+    pub memory_current_bytes: u64,
+    pub memory_max_bytes: Option<u64>,
+    pub cpu_max_cores: Option<f32>,
+    pub cpu_usage: Duration,
+}
+
+/// Cumulative counters for one child cgroup, as listed by
+/// `CgroupReader::units`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitStats {
+    pub name: String,
+    pub cpu_usage: Duration,
+    pub memory_current_bytes: u64,
+    pub io_read_bytes: u64,
+    pub io_written_bytes: u64,
+}
+
+pub struct CgroupReader {
+    procfs_root: PathBuf,
+    cgroup_root: PathBuf,
+}
+
+impl CgroupReader {
+    pub fn new() -> Self {
+        Self::with_roots("/proc", "/sys/fs/cgroup")
+    }
+
+    pub fn with_roots(procfs_root: impl Into<PathBuf>, cgroup_root: impl Into<PathBuf>) -> Self {
+        Self {
+            procfs_root: procfs_root.into(),
+            cgroup_root: cgroup_root.into(),
+        }
+    }
+
+    /// The unified (v2) cgroup of this process, from the `0::` line of
+    /// `/proc/self/cgroup`. Hosts still on cgroup v1 don't have one.
+    pub fn current_path(&self) -> Option<String> {
+        let contents = fs::read_to_string(self.procfs_root.join("self/cgroup")).ok()?;
+        contents
+            .lines()
+            .find_map(|line| line.strip_prefix("0::"))
+            .map(|path| path.trim().to_string())
+    }
+
+    /// Reads the current cgroup. Inside a container with its own cgroup
+    /// namespace the path is `/`, but the mounted root is the container's
+    /// cgroup and still carries its limits. On the real root those files
+    /// don't exist, so the limits read as `None`.
+    pub fn read(&self) -> Option<CgroupStats> {
+        let path = self.current_path()?;
+        let dir = self.cgroup_root.join(path.trim_start_matches('/'));
+
+        Some(CgroupStats {
+            memory_current_bytes: read_memory_current(&dir)?,
+            // An unlimited `memory.max` holds the literal `max`, which
+            // doesn't parse and so reads as no limit.
+            memory_max_bytes: read_parsed(&dir.join("memory.max")),
+            cpu_max_cores: read_cpu_max(&dir),
+            cpu_usage: read_cpu_usage(&dir).unwrap_or_default(),
+            path,
+        })
+    }
+
+    /// Reads every child cgroup of `parent` (e.g. `system.slice`), sorted by
+    /// name. cgroup v2 counters are hierarchical, so each child's figures
+    /// already include its own descendants.
+    pub fn units(&self, parent: &str) -> Vec<UnitStats> {
+        let dir = self.cgroup_root.join(parent.trim_start_matches('/'));
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut units: Vec<UnitStats> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .map(|e| {
+                let path = e.path();
+                let (io_read_bytes, io_written_bytes) = read_io_bytes(&path);
+
+                UnitStats {
+                    name: e.file_name().to_string_lossy().into_owned(),
+                    cpu_usage: read_cpu_usage(&path).unwrap_or_default(),
+                    memory_current_bytes: read_memory_current(&path).unwrap_or(0),
+                    io_read_bytes,
+                    io_written_bytes,
+                }
+            })
+            .collect();
+        units.sort_by(|a, b| a.name.cmp(&b.name));
+
+        units
+    }
+}
+
+impl Default for CgroupReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn read_memory_current(dir: &Path) -> Option<u64> {
+    read_parsed(&dir.join("memory.current"))
+}
+
+/// Total CPU time from the `usage_usec` line of `cpu.stat`.
+pub fn read_cpu_usage(dir: &Path) -> Option<Duration> {
+    let contents = fs::read_to_string(dir.join("cpu.stat")).ok()?;
+    contents.lines().find_map(|line| {
+        let usec = line.strip_prefix("usage_usec ")?.trim().parse().ok()?;
+        Some(Duration::from_micros(usec))
+    })
+}
+
+/// Sums `rbytes` and `wbytes` across every device line of `io.stat`, e.g.
+/// `8:0 rbytes=4096 wbytes=0 rios=1 wios=0 dbytes=0 dios=0`.
+fn read_io_bytes(dir: &Path) -> (u64, u64) {
+    let Ok(contents) = fs::read_to_string(dir.join("io.stat")) else {
+        return (0, 0);
+    };
+
+    let mut read = 0;
+    let mut written = 0;
+    for field in contents.split_whitespace() {
+        match field.split_once('=') {
+            Some(("rbytes", value)) => read += value.parse::<u64>().unwrap_or(0),
+            Some(("wbytes", value)) => written += value.parse::<u64>().unwrap_or(0),
+            _ => {}
+        }
+    }
+
+    (read, written)
+}
+
+/// `cpu.max` holds `<quota> <period>` in microseconds, with `max` for an
+/// unlimited quota. The ratio is how many CPUs' worth of time is allowed.
+fn read_cpu_max(dir: &Path) -> Option<f32> {
+    let contents = read_trimmed(&dir.join("cpu.max"))?;
+    let (quota, period) = contents.split_once(' ')?;
+    let quota: f32 = quota.parse().ok()?;
+    let period: f32 = period.parse().ok()?;
+
+    (period > 0.0).then(|| quota / period)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::sysfs::write_fixture;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_reads_limited_service_cgroup() {
+        let proc = tempdir().unwrap();
+        let cgroup = tempdir().unwrap();
+        write_fixture(
+            &proc.path().join("self/cgroup"),
+            "0::/system.slice/perch.service",
+        );
+        let unit = cgroup.path().join("system.slice/perch.service");
+        write_fixture(&unit.join("memory.current"), "52428800");
+        write_fixture(&unit.join("memory.max"), "104857600");
+        write_fixture(&unit.join("cpu.max"), "150000 100000");
+        write_fixture(
+            &unit.join("cpu.stat"),
+            "usage_usec 2500000\nuser_usec 2000000",
+        );
+
+        let stats = CgroupReader::with_roots(proc.path(), cgroup.path())
+            .read()
+            .unwrap();
+
+        assert_eq!(stats.path, "/system.slice/perch.service");
+        assert_eq!(stats.memory_current_bytes, 52_428_800);
+        assert_eq!(stats.memory_max_bytes, Some(104_857_600));
+        assert_eq!(stats.cpu_max_cores, Some(1.5));
+        assert_eq!(stats.cpu_usage, Duration::from_millis(2500));
+    }
+
+    #[test]
+    fn test_unlimited_cgroup_reads_max_as_none() {
+        let proc = tempdir().unwrap();
+        let cgroup = tempdir().unwrap();
+        write_fixture(&proc.path().join("self/cgroup"), "0::/");
+        write_fixture(&cgroup.path().join("memory.current"), "1000");
+        write_fixture(&cgroup.path().join("memory.max"), "max");
+        write_fixture(&cgroup.path().join("cpu.max"), "max 100000");
+
+        let stats = CgroupReader::with_roots(proc.path(), cgroup.path())
+            .read()
+            .unwrap();
+
+        assert_eq!(stats.memory_max_bytes, None);
+        assert_eq!(stats.cpu_max_cores, None);
+    }
+
+    #[test]
+    fn test_units_lists_child_cgroups() {
+        let cgroup = tempdir().unwrap();
+        let slice = cgroup.path().join("system.slice");
+        write_fixture(&slice.join("cpu.stat"), "usage_usec 99");
+        write_fixture(&slice.join("sshd.service/cpu.stat"), "usage_usec 3000000");
+        write_fixture(&slice.join("sshd.service/memory.current"), "4096");
+        write_fixture(
+            &slice.join("sshd.service/io.stat"),
+            "8:0 rbytes=100 wbytes=50 rios=1 wios=1\n259:0 rbytes=20 wbytes=5 rios=1 wios=1",
+        );
+        write_fixture(&slice.join("cron.service/memory.current"), "1024");
+
+        let units = CgroupReader::with_roots(cgroup.path(), cgroup.path()).units("/system.slice");
+
+        assert_eq!(units.len(), 2);
+        assert_eq!(units[0].name, "cron.service");
+        assert_eq!(units[0].cpu_usage, Duration::ZERO);
+        assert_eq!(units[1].cpu_usage, Duration::from_secs(3));
+        assert_eq!(units[1].memory_current_bytes, 4096);
+        assert_eq!(
+            (units[1].io_read_bytes, units[1].io_written_bytes),
+            (120, 55)
+        );
+        assert!(
+            CgroupReader::with_roots(cgroup.path(), cgroup.path())
+                .units("missing")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_cgroup_v1_host_has_no_unified_path() {
+        let proc = tempdir().unwrap();
+        write_fixture(&proc.path().join("self/cgroup"), "4:memory:/\n1:cpu:/");
+
+        let reader = CgroupReader::with_roots(proc.path(), proc.path());
+        assert_eq!(reader.current_path(), None);
+        assert!(reader.read().is_none());
+    }
+}
@@ -0,0 +1,41 @@
+use std::time::{Duration, Instant, SystemTime};
+
+/// Where `SystemMonitor` gets the time from. Elapsed time for rates always
+/// comes from `monotonic`, which can't jump when the wall clock is changed;
+/// `wall` is only used to timestamp samples and to spot suspends.
+pub trait Clock: Send {
+    /// Time since an arbitrary fixed point. Must never go backwards.
+    fn monotonic(&self) -> Duration;
+
+    fn wall(&self) -> SystemTime;
+}
+
+/// The real clocks. On Linux `Instant` is `CLOCK_MONOTONIC`, which stops
+/// while the machine is suspended, whereas the wall clock keeps going.
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn monotonic(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn wall(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
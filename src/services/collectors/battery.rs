@@ -0,0 +1,29 @@
+use crate::models::SystemMetrics;
+use crate::services::PowerSupplyReader;
+use crate::services::collectors::{CollectContext, MetricCollector};
+
+pub struct BatteryCollector {
+    reader: PowerSupplyReader,
+}
+
+impl BatteryCollector {
+    pub fn new(reader: PowerSupplyReader) -> Self {
+        Self { reader }
+    }
+}
+
+impl Default for BatteryCollector {
+    fn default() -> Self {
+        Self::new(PowerSupplyReader::new())
+    }
+}
+
+impl MetricCollector for BatteryCollector {
+    fn name(&self) -> &'static str {
+        "battery"
+    }
+
+    fn collect(&mut self, _ctx: &CollectContext, metrics: &mut SystemMetrics) {
+        metrics.batteries = self.reader.read();
+    }
+}
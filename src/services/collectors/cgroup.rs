@@ -0,0 +1,148 @@
+use crate::models::{CgroupMetrics, SystemMetrics};
+use crate::services::CgroupReader;
+use crate::services::collectors::{CollectContext, MetricCollector};
+use std::time::Duration;
+
+/// Scopes usage to perch's own cgroup when that cgroup has limits. It
+/// compares those limits against the host totals, so `SystemMonitor` must
+/// run it after the CPU and memory collectors.
+pub struct CgroupCollector {
+    reader: CgroupReader,
+    previous_usage: Option<Duration>,
+}
+
+impl CgroupCollector {
+    pub fn new(reader: CgroupReader) -> Self {
+        Self {
+            reader,
+            previous_usage: None,
+        }
+    }
+}
+
+impl Default for CgroupCollector {
+    fn default() -> Self {
+        Self::new(CgroupReader::new())
+    }
+}
+
+impl MetricCollector for CgroupCollector {
+    fn name(&self) -> &'static str {
+        "cgroup"
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, metrics: &mut SystemMetrics) {
+        let Some(stats) = self.reader.read() else {
+            metrics.cgroup = None;
+            return;
+        };
+        let previous_usage = self.previous_usage.replace(stats.cpu_usage);
+
+        // A limit at or above what the host has can never be hit, so it's
+        // no different from having none.
+        let host_cores = metrics.cpu.per_core.len().max(1) as f32;
+        let memory_limit_bytes = stats
+            .memory_max_bytes
+            .filter(|&limit| metrics.memory.total_bytes == 0 || limit < metrics.memory.total_bytes);
+        let cpu_limit_cores = stats.cpu_max_cores.filter(|&cores| cores < host_cores);
+
+        if memory_limit_bytes.is_none() && cpu_limit_cores.is_none() {
+            metrics.cgroup = None;
+            return;
+        }
+
+        let cpu_usage_percentage = match previous_usage {
+            Some(previous) if ctx.elapsed_secs > 0.0 => {
+                let used = stats.cpu_usage.saturating_sub(previous).as_secs_f64();
+                let cores = cpu_limit_cores.unwrap_or(host_cores) as f64;
+                (used / (ctx.elapsed_secs * cores) * 100.0).min(100.0) as f32
+            }
+            _ => 0.0,
+        };
+
+        let memory_usage_percentage = memory_limit_bytes
+            .filter(|&limit| limit > 0)
+            .map(|limit| stats.memory_current_bytes as f32 / limit as f32 * 100.0);
+
+        metrics.cgroup = Some(CgroupMetrics {
+            path: stats.path,
+            memory_current_bytes: stats.memory_current_bytes,
+            memory_limit_bytes,
+            cpu_limit_cores,
+            cpu_usage_percentage,
+            memory_usage_percentage,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::sysfs::write_fixture;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_cpu_usage_is_relative_to_quota() {
+        let proc = tempdir().unwrap();
+        let cgroup = tempdir().unwrap();
+        write_fixture(&proc.path().join("self/cgroup"), "0::/app.slice");
+        let dir = cgroup.path().join("app.slice");
+        write_fixture(&dir.join("memory.current"), "100");
+        write_fixture(&dir.join("memory.max"), "max");
+        write_fixture(&dir.join("cpu.max"), "50000 100000");
+        write_fixture(&dir.join("cpu.stat"), "usage_usec 1000000");
+
+        let mut collector =
+            CgroupCollector::new(CgroupReader::with_roots(proc.path(), cgroup.path()));
+        let mut metrics = SystemMetrics::zero();
+        let ctx = CollectContext { elapsed_secs: 2.0 };
+        collector.collect(&ctx, &mut metrics);
+        assert_eq!(metrics.cgroup.as_ref().unwrap().cpu_usage_percentage, 0.0);
+
+        // Half a second of CPU over two seconds, against half a core.
+        write_fixture(&dir.join("cpu.stat"), "usage_usec 1500000");
+        collector.collect(&ctx, &mut metrics);
+
+        let cgroup = metrics.cgroup.unwrap();
+        assert_eq!(cgroup.cpu_limit_cores, Some(0.5));
+        assert_eq!(cgroup.memory_limit_bytes, None);
+        assert_eq!(cgroup.memory_usage_percentage, None);
+        assert_eq!(cgroup.cpu_usage_percentage, 50.0);
+    }
+
+    #[test]
+    fn test_memory_usage_is_relative_to_limit() {
+        let proc = tempdir().unwrap();
+        let cgroup = tempdir().unwrap();
+        write_fixture(&proc.path().join("self/cgroup"), "0::/app.slice");
+        let dir = cgroup.path().join("app.slice");
+        write_fixture(&dir.join("memory.current"), "256");
+        write_fixture(&dir.join("memory.max"), "1024");
+
+        let mut collector =
+            CgroupCollector::new(CgroupReader::with_roots(proc.path(), cgroup.path()));
+        let mut metrics = SystemMetrics::zero();
+        metrics.memory.total_bytes = 4096;
+
+        collector.collect(&CollectContext { elapsed_secs: 1.0 }, &mut metrics);
+        assert_eq!(metrics.cgroup.unwrap().memory_usage_percentage, Some(25.0));
+    }
+
+    #[test]
+    fn test_unlimited_cgroup_reports_host_scope() {
+        let proc = tempdir().unwrap();
+        let cgroup = tempdir().unwrap();
+        write_fixture(&proc.path().join("self/cgroup"), "0::/user.slice");
+        let dir = cgroup.path().join("user.slice");
+        write_fixture(&dir.join("memory.current"), "100");
+        write_fixture(&dir.join("memory.max"), "4096");
+
+        let mut collector =
+            CgroupCollector::new(CgroupReader::with_roots(proc.path(), cgroup.path()));
+        let mut metrics = SystemMetrics::zero();
+        metrics.memory.total_bytes = 2048;
+
+        collector.collect(&CollectContext { elapsed_secs: 1.0 }, &mut metrics);
+        assert!(metrics.cgroup.is_none());
+    }
+}
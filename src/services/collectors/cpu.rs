@@ -0,0 +1,189 @@
+use crate::models::{CPUMetrics, CoreKind, CoreMetrics, CpuTimeBreakdown, SystemMetrics};
+use crate::services::collectors::{CollectContext, MetricCollector};
+use crate::services::cpu_topology::CoreTopology;
+use crate::services::proc_stat::ProcStat;
+use crate::services::{CpuTopologyReader, ProcStatReader};
+use sysinfo::{CpuRefreshKind, RefreshKind, System};
+
+pub struct CpuCollector {
+    system: System,
+    topology: CpuTopologyReader,
+    layout: Vec<Option<CoreTopology>>,
+    refresh_kind: CpuRefreshKind,
+    proc_stat: ProcStatReader,
+    previous_stat: Option<ProcStat>,
+}
+
+impl CpuCollector {
+    pub fn new(topology: CpuTopologyReader, proc_stat: ProcStatReader) -> Self {
+        // Usage needs a previous reading to diff against, so prime it here.
+        let system = System::new_with_specifics(
+            RefreshKind::nothing().with_cpu(CpuRefreshKind::nothing().with_cpu_usage()),
+        );
+        let layout = topology.read(system.cpus().len());
+
+        // Without cpufreq in sysfs (e.g. macOS, or VMs without a cpufreq
+        // driver), ask sysinfo for frequencies instead, at the cost of a
+        // slightly heavier refresh.
+        let has_cpufreq =
+            (0..system.cpus().len()).any(|cpu| topology.current_frequency_mhz(cpu).is_some());
+        let refresh_kind = if has_cpufreq {
+            CpuRefreshKind::nothing().with_cpu_usage()
+        } else {
+            CpuRefreshKind::nothing().with_cpu_usage().with_frequency()
+        };
+
+        Self {
+            system,
+            topology,
+            layout,
+            refresh_kind,
+            previous_stat: proc_stat.read(),
+            proc_stat,
+        }
+    }
+
+    /// Time-in-state for the whole machine and each core since the previous
+    /// sample. Empty on platforms without `/proc/stat`.
+    fn read_breakdowns(&mut self) -> (CpuTimeBreakdown, Vec<CpuTimeBreakdown>) {
+        let Some(current) = self.proc_stat.read() else {
+            return (CpuTimeBreakdown::default(), Vec::new());
+        };
+
+        let breakdowns = match &self.previous_stat {
+            Some(previous) => {
+                let total = current.total.breakdown_since(&previous.total);
+                let mut per_core = vec![CpuTimeBreakdown::default(); self.system.cpus().len()];
+                for (index, times) in &current.per_cpu {
+                    let before = previous.per_cpu.iter().find(|(i, _)| i == index);
+                    if let (Some(slot), Some((_, before))) = (per_core.get_mut(*index), before) {
+                        *slot = times.breakdown_since(before);
+                    }
+                }
+                (total, per_core)
+            }
+            None => (CpuTimeBreakdown::default(), Vec::new()),
+        };
+
+        self.previous_stat = Some(current);
+        breakdowns
+    }
+}
+
+impl Default for CpuCollector {
+    fn default() -> Self {
+        Self::new(CpuTopologyReader::new(), ProcStatReader::new())
+    }
+}
+
+impl MetricCollector for CpuCollector {
+    fn name(&self) -> &'static str {
+        "cpu"
+    }
+
+    fn collect(&mut self, _ctx: &CollectContext, metrics: &mut SystemMetrics) {
+        self.system.refresh_cpu_specifics(self.refresh_kind);
+        let (breakdown, core_breakdowns) = self.read_breakdowns();
+
+        let per_core: Vec<CoreMetrics> = self
+            .system
+            .cpus()
+            .iter()
+            .enumerate()
+            .map(|(id, cpu)| {
+                let topology = self.layout.get(id).and_then(Option::as_ref);
+
+                CoreMetrics {
+                    id,
+                    usage_percentage: cpu.cpu_usage(),
+                    frequency_mhz: self
+                        .topology
+                        .current_frequency_mhz(id)
+                        .unwrap_or_else(|| cpu.frequency()),
+                    min_frequency_mhz: topology.map_or(0, |t| t.min_frequency_mhz),
+                    max_frequency_mhz: topology.map_or(0, |t| t.max_frequency_mhz),
+                    package_id: topology.map_or(0, |t| t.package_id),
+                    core_id: topology.map_or(id as u32, |t| t.core_id),
+                    kind: topology.map_or(CoreKind::Unknown, |t| t.kind),
+                    breakdown: core_breakdowns.get(id).copied().unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        metrics.cpu = CPUMetrics {
+            usage_percentage: self.system.global_cpu_usage(),
+            per_core,
+            breakdown,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_cpu_collector_does_not_load_processes() {
+        let mut collector = CpuCollector::default();
+        let mut metrics = SystemMetrics::zero();
+        collector.collect(&CollectContext { elapsed_secs: 1.0 }, &mut metrics);
+
+        assert!(collector.system.processes().is_empty());
+        assert!(!metrics.cpu.per_core.is_empty());
+    }
+
+    #[test]
+    fn test_frequencies_from_sysinfo_without_cpufreq() {
+        let dir = tempfile::tempdir().unwrap();
+        let topology = dir.path().join("devices/system/cpu/cpu0/topology");
+        std::fs::create_dir_all(&topology).unwrap();
+        std::fs::write(topology.join("physical_package_id"), "0\n").unwrap();
+        std::fs::write(topology.join("core_id"), "0\n").unwrap();
+
+        let collector = CpuCollector::new(
+            CpuTopologyReader::with_root(dir.path()),
+            ProcStatReader::with_root(dir.path()),
+        );
+        assert!(collector.refresh_kind.frequency());
+
+        let cpufreq = dir.path().join("devices/system/cpu/cpu0/cpufreq");
+        std::fs::create_dir_all(&cpufreq).unwrap();
+        std::fs::write(cpufreq.join("scaling_cur_freq"), "2400000\n").unwrap();
+
+        let collector = CpuCollector::new(
+            CpuTopologyReader::with_root(dir.path()),
+            ProcStatReader::with_root(dir.path()),
+        );
+        assert!(!collector.refresh_kind.frequency());
+    }
+
+    #[test]
+    fn test_cpu_collector_breakdown_from_fixture() {
+        let dir = tempfile::tempdir().unwrap();
+        let stat = dir.path().join("stat");
+        std::fs::write(
+            &stat,
+            "cpu  10 0 10 80 0 0 0 0 0 0\ncpu0 10 0 10 80 0 0 0 0 0 0\n",
+        )
+        .unwrap();
+
+        let mut collector = CpuCollector::new(
+            CpuTopologyReader::with_root(dir.path()),
+            ProcStatReader::with_root(dir.path()),
+        );
+
+        std::fs::write(
+            &stat,
+            "cpu  40 0 20 100 20 0 0 20 0 0\ncpu0 40 0 20 100 20 0 0 20 0 0\n",
+        )
+        .unwrap();
+        let mut metrics = SystemMetrics::zero();
+        collector.collect(&CollectContext { elapsed_secs: 1.0 }, &mut metrics);
+
+        assert_approx_eq!(metrics.cpu.breakdown.user, 30.0, 1e-3);
+        assert_approx_eq!(metrics.cpu.breakdown.iowait, 20.0, 1e-3);
+        assert_approx_eq!(metrics.cpu.breakdown.steal, 20.0, 1e-3);
+        assert_eq!(metrics.cpu.per_core[0].breakdown, metrics.cpu.breakdown);
+    }
+}
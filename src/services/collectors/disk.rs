@@ -0,0 +1,159 @@
+use crate::models::{DiskDeviceMetrics, DiskMetrics, Smoothing, SystemMetrics};
+use crate::services::DiskStatsReader;
+use crate::services::collectors::{CollectContext, MetricCollector, SmoothedRates};
+
+pub struct DiskCollector {
+    reader: DiskStatsReader,
+    read: SmoothedRates<String>,
+    written: SmoothedRates<String>,
+}
+
+impl DiskCollector {
+    pub fn new(reader: DiskStatsReader, smoothing: Smoothing) -> Self {
+        Self {
+            reader,
+            read: SmoothedRates::new(smoothing),
+            written: SmoothedRates::new(smoothing),
+        }
+    }
+}
+
+impl Default for DiskCollector {
+    fn default() -> Self {
+        Self::new(DiskStatsReader::new(), Smoothing::None)
+    }
+}
+
+impl MetricCollector for DiskCollector {
+    fn name(&self) -> &'static str {
+        "disk"
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, metrics: &mut SystemMetrics) {
+        if metrics.gap_after_suspend {
+            self.read.restart_smoothing();
+            self.written.restart_smoothing();
+        }
+
+        let devices: Vec<DiskDeviceMetrics> = self
+            .reader
+            .read()
+            .into_iter()
+            .map(|stat| {
+                let read_bytes = stat.read_bytes();
+                let written_bytes = stat.written_bytes();
+                let (raw_read_rate, read_rate) =
+                    self.read
+                        .rate(stat.name.clone(), read_bytes, ctx.elapsed_secs);
+                let (raw_write_rate, write_rate) =
+                    self.written
+                        .rate(stat.name.clone(), written_bytes, ctx.elapsed_secs);
+
+                DiskDeviceMetrics {
+                    read_rate,
+                    write_rate,
+                    raw_read_rate,
+                    raw_write_rate,
+                    name: stat.name,
+                    read_bytes,
+                    written_bytes,
+                }
+            })
+            .collect();
+        self.read.finish();
+        self.written.finish();
+
+        // Totals are summed from the devices rather than tracked as counters
+        // of their own, so a disk being removed doesn't look like a reset.
+        metrics.disk = DiskMetrics {
+            read_bytes: devices.iter().map(|d| d.read_bytes).sum(),
+            written_bytes: devices.iter().map(|d| d.written_bytes).sum(),
+            read_rate: devices.iter().map(|d| d.read_rate).sum(),
+            write_rate: devices.iter().map(|d| d.write_rate).sum(),
+            raw_read_rate: devices.iter().map(|d| d.raw_read_rate).sum(),
+            raw_write_rate: devices.iter().map(|d| d.raw_write_rate).sum(),
+            devices,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_disk_rates_from_fixture() {
+        let dir = tempdir().unwrap();
+        let diskstats = dir.path().join("diskstats");
+        let reader = DiskStatsReader::with_roots(dir.path(), dir.path().join("sys"));
+        let mut collector = DiskCollector::new(reader, Smoothing::None);
+        let ctx = CollectContext { elapsed_secs: 2.0 };
+
+        fs::write(
+            &diskstats,
+            " 8 0 sda 1 0 100 0 1 0 200 0 0 0 0\n 8 16 sdb 1 0 4 0 1 0 8 0 0 0 0\n",
+        )
+        .unwrap();
+        let mut metrics = SystemMetrics::zero();
+        collector.collect(&ctx, &mut metrics);
+
+        fs::write(
+            &diskstats,
+            " 8 0 sda 1 0 104 0 1 0 208 0 0 0 0\n 8 16 sdb 1 0 4 0 1 0 8 0 0 0 0\n",
+        )
+        .unwrap();
+        collector.collect(&ctx, &mut metrics);
+
+        assert_eq!(metrics.disk.devices.len(), 2);
+        assert_eq!(metrics.disk.read_bytes, 108 * 512);
+        assert_eq!(metrics.disk.devices[0].read_rate, 1024.0);
+        assert_eq!(metrics.disk.devices[0].write_rate, 2048.0);
+        assert_eq!(metrics.disk.devices[1].read_rate, 0.0);
+        assert_eq!(metrics.disk.read_rate, 1024.0);
+    }
+
+    #[test]
+    fn test_new_disk_has_no_rate_on_first_sample() {
+        let dir = tempdir().unwrap();
+        let diskstats = dir.path().join("diskstats");
+        let reader = DiskStatsReader::with_roots(dir.path(), dir.path().join("sys"));
+        let mut collector = DiskCollector::new(reader, Smoothing::None);
+        let ctx = CollectContext { elapsed_secs: 1.0 };
+
+        fs::write(&diskstats, " 8 0 sda 1 0 100 0 1 0 200 0 0 0 0\n").unwrap();
+        let mut metrics = SystemMetrics::zero();
+        collector.collect(&ctx, &mut metrics);
+        assert_eq!(metrics.disk.read_rate, 0.0);
+
+        fs::write(
+            &diskstats,
+            " 8 0 sda 1 0 102 0 1 0 200 0 0 0 0\n 8 16 sdb 1 0 900000 0 1 0 8 0 0 0 0\n",
+        )
+        .unwrap();
+        collector.collect(&ctx, &mut metrics);
+
+        assert_eq!(metrics.disk.devices[1].read_rate, 0.0);
+        assert_eq!(metrics.disk.read_rate, 1024.0);
+    }
+
+    #[test]
+    fn test_smoothed_disk_rate_keeps_raw() {
+        let dir = tempdir().unwrap();
+        let diskstats = dir.path().join("diskstats");
+        let reader = DiskStatsReader::with_roots(dir.path(), dir.path().join("sys"));
+        let mut collector = DiskCollector::new(reader, Smoothing::Window { window_secs: 2.0 });
+        let ctx = CollectContext { elapsed_secs: 1.0 };
+        let mut metrics = SystemMetrics::zero();
+
+        for sectors in [0, 0, 8] {
+            let line = format!(" 8 0 sda 1 0 {} 0 1 0 0 0 0 0 0\n", sectors);
+            fs::write(&diskstats, line).unwrap();
+            collector.collect(&ctx, &mut metrics);
+        }
+
+        assert_eq!(metrics.disk.raw_read_rate, 4096.0);
+        assert_eq!(metrics.disk.read_rate, 2048.0);
+    }
+}
@@ -0,0 +1,24 @@
+use crate::models::SystemMetrics;
+use crate::services::collectors::{CollectContext, MetricCollector};
+use crate::services::{MountFilter, MountReader};
+
+pub struct FilesystemCollector {
+    reader: MountReader,
+    filter: MountFilter,
+}
+
+impl FilesystemCollector {
+    pub fn new(reader: MountReader, filter: MountFilter) -> Self {
+        Self { reader, filter }
+    }
+}
+
+impl MetricCollector for FilesystemCollector {
+    fn name(&self) -> &'static str {
+        "filesystems"
+    }
+
+    fn collect(&mut self, _ctx: &CollectContext, metrics: &mut SystemMetrics) {
+        metrics.filesystems = self.reader.read(&self.filter);
+    }
+}
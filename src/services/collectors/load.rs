@@ -0,0 +1,122 @@
+use crate::models::{LoadMetrics, SystemMetrics};
+use crate::services::collectors::{CollectContext, MetricCollector};
+use crate::services::proc_counts::{ProcessCountReader, ProcessCounts};
+use std::time::{Duration, SystemTime};
+use sysinfo::System;
+
+/// How often every process is walked for the process and zombie counts.
+/// The running and thread counts come from `/proc/loadavg` on every sample.
+const PROCESS_WALK_INTERVAL_SECS: f64 = 10.0;
+
+pub struct LoadCollector {
+    counts: ProcessCountReader,
+    walked: ProcessCounts,
+    /// Time since the last walk, or `None` before the first.
+    since_walk_secs: Option<f64>,
+}
+
+impl LoadCollector {
+    pub fn new(counts: ProcessCountReader) -> Self {
+        Self {
+            counts,
+            walked: ProcessCounts::default(),
+            since_walk_secs: None,
+        }
+    }
+
+    fn read_counts(&mut self, elapsed_secs: f64) -> ProcessCounts {
+        let since_walk = self.since_walk_secs.map(|secs| secs + elapsed_secs);
+        if since_walk.is_none_or(|secs| secs >= PROCESS_WALK_INTERVAL_SECS) {
+            self.walked = self.counts.read();
+            self.since_walk_secs = Some(0.0);
+        } else {
+            self.since_walk_secs = since_walk;
+        }
+
+        let mut counts = self.walked;
+        if let Some((running, threads)) = self.counts.read_loadavg() {
+            counts.running = running;
+            counts.threads = threads;
+        }
+        counts
+    }
+}
+
+impl Default for LoadCollector {
+    fn default() -> Self {
+        Self::new(ProcessCountReader::new())
+    }
+}
+
+impl MetricCollector for LoadCollector {
+    fn name(&self) -> &'static str {
+        "load"
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, metrics: &mut SystemMetrics) {
+        let load = System::load_average();
+        let counts = self.read_counts(ctx.elapsed_secs);
+
+        metrics.load = LoadMetrics {
+            load_one: load.one,
+            load_five: load.five,
+            load_fifteen: load.fifteen,
+            uptime: Duration::from_secs(System::uptime()),
+            boot_time: SystemTime::UNIX_EPOCH + Duration::from_secs(System::boot_time()),
+            process_count: counts.processes,
+            thread_count: counts.threads,
+            running_count: counts.running,
+            zombie_count: counts.zombies,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::sysfs::write_fixture;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    fn add_process(root: &Path, pid: u32, state: char) {
+        let stat = format!(
+            "{} (proc) {} 1 1 1 0 -1 4194304 78 0 0 0 0 0 0 0 20 0 1 0 179774 2703360 286",
+            pid, state
+        );
+        write_fixture(&root.join(pid.to_string()).join("stat"), &stat);
+    }
+
+    #[test]
+    fn test_process_walk_runs_at_lower_rate() {
+        let dir = tempdir().unwrap();
+        add_process(dir.path(), 1, 'S');
+        add_process(dir.path(), 2, 'Z');
+        write_fixture(&dir.path().join("loadavg"), "0.10 0.20 0.30 1/40 2");
+
+        let mut collector = LoadCollector::new(ProcessCountReader::with_root(dir.path()));
+        let counts = collector.read_counts(1.0);
+        assert_eq!((counts.processes, counts.zombies), (2, 1));
+        assert_eq!((counts.running, counts.threads), (1, 40));
+
+        add_process(dir.path(), 3, 'R');
+        write_fixture(&dir.path().join("loadavg"), "0.10 0.20 0.30 2/45 3");
+        let counts = collector.read_counts(5.0);
+        assert_eq!(counts.processes, 2);
+        assert_eq!((counts.running, counts.threads), (2, 45));
+
+        let counts = collector.read_counts(5.0);
+        assert_eq!(counts.processes, 3);
+    }
+
+    #[test]
+    fn test_load_collector_reads_live_system() {
+        let mut collector = LoadCollector::default();
+        let mut metrics = SystemMetrics::zero();
+        collector.collect(&CollectContext { elapsed_secs: 1.0 }, &mut metrics);
+
+        assert!(metrics.load.uptime > Duration::ZERO);
+        assert!(metrics.load.boot_time < SystemTime::now());
+        assert!(metrics.load.process_count > 0);
+        assert!(metrics.load.thread_count >= metrics.load.process_count);
+    }
+}
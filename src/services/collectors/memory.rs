@@ -0,0 +1,70 @@
+use crate::models::{MemoryMetrics, SystemMetrics};
+use crate::services::MeminfoReader;
+use crate::services::collectors::{CollectContext, MetricCollector};
+use sysinfo::{MemoryRefreshKind, System};
+
+/// Totals come from sysinfo so they work everywhere; the breakdown is only
+/// filled in where `/proc/meminfo` exists.
+pub struct MemoryCollector {
+    system: System,
+    meminfo: MeminfoReader,
+}
+
+impl MemoryCollector {
+    pub fn new(meminfo: MeminfoReader) -> Self {
+        Self {
+            system: System::new(),
+            meminfo,
+        }
+    }
+}
+
+impl Default for MemoryCollector {
+    fn default() -> Self {
+        Self::new(MeminfoReader::new())
+    }
+}
+
+impl MetricCollector for MemoryCollector {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn collect(&mut self, _ctx: &CollectContext, metrics: &mut SystemMetrics) {
+        self.system
+            .refresh_memory_specifics(MemoryRefreshKind::nothing().with_ram().with_swap());
+
+        metrics.memory = MemoryMetrics {
+            total_bytes: self.system.total_memory(),
+            used_bytes: self.system.used_memory(),
+            available_bytes: self.system.available_memory(),
+            swap_total_bytes: self.system.total_swap(),
+            swap_used_bytes: self.system.used_swap(),
+            breakdown: self.meminfo.read().unwrap_or_default(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_memory_collector_reads_breakdown_from_fixture() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("meminfo"),
+            "MemFree: 100 kB\nBuffers: 20 kB\nCached: 300 kB\nShmem: 10 kB\n",
+        )
+        .unwrap();
+        let mut collector = MemoryCollector::new(MeminfoReader::with_roots(dir.path(), dir.path()));
+        let mut metrics = SystemMetrics::zero();
+
+        collector.collect(&CollectContext { elapsed_secs: 1.0 }, &mut metrics);
+
+        assert_eq!(metrics.memory.breakdown.buffers_bytes, 20 * 1024);
+        assert_eq!(metrics.memory.breakdown.cache_bytes(), 290 * 1024);
+    }
+}
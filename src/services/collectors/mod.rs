@@ -0,0 +1,47 @@
+pub mod battery;
+pub mod cgroup;
+pub mod cpu;
+pub mod disk;
+pub mod filesystems;
+pub mod load;
+pub mod memory;
+pub mod network;
+pub mod pressure;
+pub mod process;
+pub mod rate;
+pub mod sensors;
+pub mod smoothing;
+pub mod units;
+
+pub use battery::BatteryCollector;
+pub use cgroup::CgroupCollector;
+pub use cpu::CpuCollector;
+pub use disk::DiskCollector;
+pub use filesystems::FilesystemCollector;
+pub use load::LoadCollector;
+pub use memory::MemoryCollector;
+pub use network::NetworkCollector;
+pub use pressure::PressureCollector;
+pub use process::ProcessCollector;
+pub use rate::CounterRates;
+pub use sensors::SensorsCollector;
+pub use smoothing::SmoothedRates;
+pub use units::UnitCollector;
+
+use crate::models::SystemMetrics;
+
+/// Timing information shared by every collector for a single sample.
+#[derive(Debug, Clone, Copy)]
+pub struct CollectContext {
+    pub elapsed_secs: f64,
+}
+
+/// A source of metrics that `SystemMonitor` polls once per sample. Each
+/// collector owns whatever handles it reads from and fills in its own part
+/// of the snapshot, leaving the rest untouched.
+pub trait MetricCollector: Send {
+    /// Stable identifier, used to turn the collector off from `UserConfig`.
+    fn name(&self) -> &'static str;
+
+    fn collect(&mut self, ctx: &CollectContext, metrics: &mut SystemMetrics);
+}
@@ -0,0 +1,109 @@
+use crate::models::{InterfaceMetrics, NetworkMetrics, Smoothing, SystemMetrics};
+use crate::services::NetInterfaceReader;
+use crate::services::collectors::{CollectContext, MetricCollector, SmoothedRates};
+use sysinfo::Networks;
+
+pub struct NetworkCollector {
+    networks: Networks,
+    interfaces: NetInterfaceReader,
+    include_virtual: bool,
+    received: SmoothedRates<String>,
+    transmitted: SmoothedRates<String>,
+}
+
+impl NetworkCollector {
+    pub fn new(include_virtual: bool, smoothing: Smoothing) -> Self {
+        Self {
+            networks: Networks::new_with_refreshed_list(),
+            interfaces: NetInterfaceReader::new(),
+            include_virtual,
+            received: SmoothedRates::new(smoothing),
+            transmitted: SmoothedRates::new(smoothing),
+        }
+    }
+}
+
+impl MetricCollector for NetworkCollector {
+    fn name(&self) -> &'static str {
+        "network"
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, metrics: &mut SystemMetrics) {
+        self.networks.refresh(true);
+        if metrics.gap_after_suspend {
+            self.received.restart_smoothing();
+            self.transmitted.restart_smoothing();
+        }
+
+        let mut interfaces = Vec::new();
+
+        for (name, data) in &self.networks {
+            let received_bytes = data.total_received();
+            let transmitted_bytes = data.total_transmitted();
+            let (raw_received_rate, received_rate) =
+                self.received
+                    .rate(name.clone(), received_bytes, ctx.elapsed_secs);
+            let (raw_transmitted_rate, transmitted_rate) =
+                self.transmitted
+                    .rate(name.clone(), transmitted_bytes, ctx.elapsed_secs);
+
+            interfaces.push(InterfaceMetrics {
+                name: name.clone(),
+                received_bytes,
+                transmitted_bytes,
+                received_rate,
+                transmitted_rate,
+                raw_received_rate,
+                raw_transmitted_rate,
+                is_up: self.interfaces.is_up(name),
+                is_virtual: self.interfaces.is_virtual(name),
+                mac_address: data.mac_address().to_string(),
+                ip_addresses: data.ip_networks().iter().map(|ip| ip.to_string()).collect(),
+            });
+        }
+
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+        self.received.finish();
+        self.transmitted.finish();
+
+        metrics.network = NetworkMetrics::from_interfaces(interfaces, self.include_virtual);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_network_bytes_are_cumulative() {
+        let mut collector = NetworkCollector::new(false, Smoothing::None);
+        let ctx = CollectContext { elapsed_secs: 1.0 };
+
+        let mut first = SystemMetrics::zero();
+        collector.collect(&ctx, &mut first);
+        std::thread::sleep(Duration::from_millis(200));
+        let mut second = SystemMetrics::zero();
+        collector.collect(&ctx, &mut second);
+
+        assert!(second.network.received_bytes >= first.network.received_bytes);
+        assert!(second.network.transmitted_bytes >= first.network.transmitted_bytes);
+    }
+
+    #[test]
+    fn test_network_interfaces_are_listed_individually() {
+        let mut collector = NetworkCollector::new(false, Smoothing::None);
+        let mut metrics = SystemMetrics::zero();
+        collector.collect(&CollectContext { elapsed_secs: 1.0 }, &mut metrics);
+
+        let physical: u64 = metrics
+            .network
+            .interfaces
+            .iter()
+            .filter(|i| !i.is_virtual)
+            .map(|i| i.received_bytes)
+            .sum();
+
+        assert_eq!(metrics.network.received_bytes, physical);
+    }
+}
@@ -0,0 +1,29 @@
+use crate::models::SystemMetrics;
+use crate::services::PressureReader;
+use crate::services::collectors::{CollectContext, MetricCollector};
+
+pub struct PressureCollector {
+    reader: PressureReader,
+}
+
+impl PressureCollector {
+    pub fn new(reader: PressureReader) -> Self {
+        Self { reader }
+    }
+}
+
+impl Default for PressureCollector {
+    fn default() -> Self {
+        Self::new(PressureReader::new())
+    }
+}
+
+impl MetricCollector for PressureCollector {
+    fn name(&self) -> &'static str {
+        "pressure"
+    }
+
+    fn collect(&mut self, _ctx: &CollectContext, metrics: &mut SystemMetrics) {
+        metrics.pressure = self.reader.read();
+    }
+}
@@ -0,0 +1,108 @@
+use crate::models::{ProcessMetrics, ProcessSort, SystemMetrics, top_processes};
+use crate::services::collectors::{CollectContext, MetricCollector};
+use std::collections::HashSet;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind, Users};
+
+/// Reads the process table. Only registered while the process section is
+/// shown, since walking every process is by far the most expensive refresh.
+pub struct ProcessCollector {
+    system: System,
+    users: Users,
+    count: usize,
+}
+
+impl ProcessCollector {
+    pub fn new(count: usize) -> Self {
+        Self {
+            system: System::new(),
+            users: Users::new_with_refreshed_list(),
+            count,
+        }
+    }
+
+    fn to_metrics(&self, process: &sysinfo::Process) -> ProcessMetrics {
+        let user = process
+            .user_id()
+            .and_then(|uid| self.users.get_user_by_id(uid))
+            .map(|user| user.name().to_string())
+            .unwrap_or_default();
+
+        let command = process
+            .cmd()
+            .iter()
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        ProcessMetrics {
+            pid: process.pid().as_u32(),
+            name: process.name().to_string_lossy().into_owned(),
+            command,
+            cpu_percentage: process.cpu_usage(),
+            rss_bytes: process.memory(),
+            user,
+        }
+    }
+}
+
+impl MetricCollector for ProcessCollector {
+    fn name(&self) -> &'static str {
+        "processes"
+    }
+
+    fn collect(&mut self, _ctx: &CollectContext, metrics: &mut SystemMetrics) {
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing()
+                .with_cpu()
+                .with_memory()
+                .with_cmd(UpdateKind::OnlyIfNotSet)
+                .with_user(UpdateKind::OnlyIfNotSet),
+        );
+
+        let all: Vec<ProcessMetrics> = self
+            .system
+            .processes()
+            .values()
+            .filter(|process| process.thread_kind().is_none())
+            .map(|process| self.to_metrics(process))
+            .collect();
+
+        // Keep the heaviest by either key so the widget can re-sort without
+        // waiting for the next sample.
+        let mut seen = HashSet::new();
+        let mut processes = Vec::new();
+        for sort in [ProcessSort::Cpu, ProcessSort::Memory] {
+            for process in top_processes(&all, sort, self.count) {
+                if seen.insert(process.pid) {
+                    processes.push(process.clone());
+                }
+            }
+        }
+
+        metrics.processes = processes;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_collector_keeps_top_by_cpu_and_memory() {
+        let mut collector = ProcessCollector::new(3);
+        let mut metrics = SystemMetrics::zero();
+        collector.collect(&CollectContext { elapsed_secs: 1.0 }, &mut metrics);
+
+        assert!(!metrics.processes.is_empty());
+        assert!(metrics.processes.len() <= 6);
+
+        let by_memory = top_processes(&metrics.processes, ProcessSort::Memory, 3);
+        assert!(
+            by_memory
+                .windows(2)
+                .all(|w| w[0].rss_bytes >= w[1].rss_bytes)
+        );
+    }
+}
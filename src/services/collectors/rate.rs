@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Turns cumulative counters (bytes, sectors) into per-second rates, one
+/// counter per key.
+///
+/// Call `rate` for every counter in a sample, then `finish` once the sample
+/// is done. A key seen for the first time has nothing to diff against and
+/// reports zero; keys missing from a sample are forgotten, so an interface
+/// that disappears and comes back starts fresh.
+pub struct CounterRates<K> {
+    previous: HashMap<K, u64>,
+    current: HashMap<K, u64>,
+}
+
+impl<K: Eq + Hash> CounterRates<K> {
+    pub fn new() -> Self {
+        Self {
+            previous: HashMap::new(),
+            current: HashMap::new(),
+        }
+    }
+
+    pub fn rate(&mut self, key: K, value: u64, elapsed_secs: f64) -> f32 {
+        self.measured_rate(key, value, elapsed_secs).unwrap_or(0.0)
+    }
+
+    /// Like `rate`, but `None` for a key seen for the first time instead of
+    /// a made-up zero.
+    pub fn measured_rate(&mut self, key: K, value: u64, elapsed_secs: f64) -> Option<f32> {
+        let delta = self
+            .previous
+            .get(&key)
+            .map(|&previous| counter_delta(previous, value));
+        self.current.insert(key, value);
+
+        match delta {
+            Some(delta) if elapsed_secs > 0.0 => Some((delta as f64 / elapsed_secs) as f32),
+            Some(_) => Some(0.0),
+            None => None,
+        }
+    }
+
+    /// Ends the current sample, dropping any key that wasn't seen in it.
+    pub fn finish(&mut self) {
+        self.previous = std::mem::take(&mut self.current);
+    }
+}
+
+impl<K: Eq + Hash> Default for CounterRates<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How far a counter advanced between two readings.
+///
+/// When it went backwards, either a 32-bit counter wrapped (some drivers
+/// still expose those) or the counter was reset, e.g. by a driver reload.
+/// A wrap is only assumed when the previous value was in the top half of
+/// the 32-bit range; otherwise it's a reset, and the new value is what
+/// accumulated since.
+fn counter_delta(previous: u64, current: u64) -> u64 {
+    const WRAP_THRESHOLD: u64 = u32::MAX as u64 / 2;
+
+    if current >= previous {
+        current - previous
+    } else if previous <= u32::MAX as u64 && previous > WRAP_THRESHOLD {
+        (u32::MAX as u64 - previous) + current + 1
+    } else {
+        current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_sample_reports_zero() {
+        let mut rates = CounterRates::new();
+        assert_eq!(rates.rate("eth0", 1_000_000_000, 1.0), 0.0);
+        rates.finish();
+
+        assert_eq!(rates.rate("eth0", 1_000_002_048, 2.0), 1024.0);
+    }
+
+    #[test]
+    fn test_first_sample_has_no_measured_rate() {
+        let mut rates = CounterRates::new();
+        assert_eq!(rates.measured_rate("eth0", 1_000_000_000, 1.0), None);
+        rates.finish();
+
+        assert_eq!(
+            rates.measured_rate("eth0", 1_000_001_024, 1.0),
+            Some(1024.0)
+        );
+    }
+
+    #[test]
+    fn test_zero_elapsed_reports_zero() {
+        let mut rates = CounterRates::new();
+        rates.rate("sda", 1024, 1.0);
+        rates.finish();
+
+        assert_eq!(rates.rate("sda", 2048, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_32_bit_wraparound() {
+        let mut rates = CounterRates::new();
+        rates.rate("eth0", u32::MAX as u64 - 99, 1.0);
+        rates.finish();
+
+        assert_eq!(rates.rate("eth0", 400, 1.0), 500.0);
+    }
+
+    #[test]
+    fn test_reset_counts_from_zero() {
+        let mut rates = CounterRates::new();
+        rates.rate("eth0", 10_000_000_000, 1.0);
+        rates.finish();
+
+        assert_eq!(rates.rate("eth0", 300, 1.0), 300.0);
+    }
+
+    #[test]
+    fn test_missing_keys_are_forgotten() {
+        let mut rates = CounterRates::new();
+        rates.rate("usb0", 5000, 1.0);
+        rates.rate("eth0", 100, 1.0);
+        rates.finish();
+
+        rates.rate("eth0", 200, 1.0);
+        rates.finish();
+
+        // usb0 was unplugged for a sample, so its old counter is stale.
+        assert_eq!(rates.rate("usb0", 9000, 1.0), 0.0);
+        assert_eq!(rates.rate("eth0", 300, 1.0), 100.0);
+    }
+}
@@ -0,0 +1,66 @@
+use crate::models::{SystemMetrics, TemperatureSensor, ThermalMetrics};
+use crate::services::HwmonReader;
+use crate::services::collectors::{CollectContext, MetricCollector};
+use sysinfo::Components;
+
+/// Reads hwmon directly where it exists so fans are included, and falls back
+/// to sysinfo's components on platforms without it.
+pub struct SensorsCollector {
+    hwmon: HwmonReader,
+    components: Option<Components>,
+}
+
+impl SensorsCollector {
+    pub fn new(hwmon: HwmonReader) -> Self {
+        Self {
+            hwmon,
+            components: None,
+        }
+    }
+
+    fn read_components(&mut self) -> ThermalMetrics {
+        let components = self
+            .components
+            .get_or_insert_with(Components::new_with_refreshed_list);
+        components.refresh(false);
+
+        let sensors = components
+            .iter()
+            .filter_map(|component| {
+                Some(TemperatureSensor {
+                    label: component.label().to_string(),
+                    current_celsius: component.temperature()?,
+                    max_celsius: component.max(),
+                    critical_celsius: component.critical(),
+                })
+            })
+            .collect();
+
+        ThermalMetrics {
+            sensors,
+            fans: Vec::new(),
+        }
+    }
+}
+
+impl Default for SensorsCollector {
+    fn default() -> Self {
+        Self::new(HwmonReader::new())
+    }
+}
+
+impl MetricCollector for SensorsCollector {
+    fn name(&self) -> &'static str {
+        "sensors"
+    }
+
+    fn collect(&mut self, _ctx: &CollectContext, metrics: &mut SystemMetrics) {
+        let thermal = self.hwmon.read();
+
+        metrics.thermal = if thermal.sensors.is_empty() && thermal.fans.is_empty() {
+            self.read_components()
+        } else {
+            thermal
+        };
+    }
+}
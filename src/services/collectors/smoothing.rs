@@ -0,0 +1,227 @@
+use crate::models::Smoothing;
+use crate::services::collectors::CounterRates;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+#[derive(Debug, Default)]
+struct SmoothingState {
+    average: f32,
+    /// Samples inside the sliding window, as (time since first sample, value).
+    window: VecDeque<(f64, f32)>,
+    age_secs: f64,
+}
+
+/// Smooths one rate per key, following the same call pattern as
+/// `CounterRates`: call `smooth` for every key in a sample, then `finish`.
+/// A key missing from a sample loses its history.
+pub struct RateSmoother<K> {
+    smoothing: Smoothing,
+    previous: HashMap<K, SmoothingState>,
+    current: HashMap<K, SmoothingState>,
+}
+
+impl<K: Eq + Hash + Clone> RateSmoother<K> {
+    pub fn new(smoothing: Smoothing) -> Self {
+        Self {
+            smoothing,
+            previous: HashMap::new(),
+            current: HashMap::new(),
+        }
+    }
+
+    pub fn smooth(&mut self, key: K, value: f32, elapsed_secs: f64) -> f32 {
+        let existing = self.previous.remove(&key);
+        let is_first = existing.is_none();
+        let mut state = existing.unwrap_or_default();
+
+        let smoothed = match self.smoothing {
+            Smoothing::None => value,
+            Smoothing::Ewma { half_life_secs } => {
+                if is_first || half_life_secs <= 0.0 {
+                    state.average = value;
+                } else {
+                    let decay = 0.5f64.powf(elapsed_secs / half_life_secs as f64) as f32;
+                    state.average = value + (state.average - value) * decay;
+                }
+                state.average
+            }
+            // A window with no width holds nothing but the newest sample.
+            Smoothing::Window { window_secs } if window_secs <= 0.0 => value,
+            Smoothing::Window { window_secs } => {
+                if !is_first {
+                    state.age_secs += elapsed_secs;
+                }
+                state.window.push_back((state.age_secs, value));
+                let oldest = state.age_secs - window_secs as f64;
+                while state.window.front().is_some_and(|&(age, _)| age <= oldest) {
+                    state.window.pop_front();
+                }
+
+                // The newest sample is never outside the window, so this
+                // can't divide by zero.
+                state.window.iter().map(|&(_, v)| v).sum::<f32>() / state.window.len() as f32
+            }
+        };
+
+        self.current.insert(key, state);
+        smoothed
+    }
+
+    pub fn finish(&mut self) {
+        self.previous = std::mem::take(&mut self.current);
+    }
+
+    /// Forgets every key's history, so the next value of each starts afresh.
+    pub fn restart(&mut self) {
+        self.previous.clear();
+        self.current.clear();
+    }
+}
+
+/// Counter rates with smoothing applied, keeping the raw rate as well.
+pub struct SmoothedRates<K> {
+    rates: CounterRates<K>,
+    smoother: RateSmoother<K>,
+}
+
+impl<K: Eq + Hash + Clone> SmoothedRates<K> {
+    pub fn new(smoothing: Smoothing) -> Self {
+        Self {
+            rates: CounterRates::new(),
+            smoother: RateSmoother::new(smoothing),
+        }
+    }
+
+    /// Returns the raw and the smoothed rate, in that order. Smoothing
+    /// starts from a key's first measured rate, not the zero reported for
+    /// its first sample.
+    pub fn rate(&mut self, key: K, value: u64, elapsed_secs: f64) -> (f32, f32) {
+        match self.rates.measured_rate(key.clone(), value, elapsed_secs) {
+            Some(raw) => (raw, self.smoother.smooth(key, raw, elapsed_secs)),
+            None => (0.0, 0.0),
+        }
+    }
+
+    /// Drops the smoothing history, e.g. after a suspend, when the old
+    /// rates say nothing about the new ones. Counters are kept.
+    pub fn restart_smoothing(&mut self) {
+        self.smoother.restart();
+    }
+
+    pub fn finish(&mut self) {
+        self.rates.finish();
+        self.smoother.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn run(smoothing: Smoothing, values: &[f32], elapsed_secs: f64) -> Vec<f32> {
+        let mut smoother = RateSmoother::new(smoothing);
+        values
+            .iter()
+            .map(|&value| {
+                let smoothed = smoother.smooth("eth0", value, elapsed_secs);
+                smoother.finish();
+                smoothed
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_no_smoothing_passes_values_through() {
+        assert_eq!(
+            run(Smoothing::None, &[1.0, 100.0, 3.0], 0.5),
+            vec![1.0, 100.0, 3.0]
+        );
+    }
+
+    #[test]
+    fn test_ewma_halves_weight_each_half_life() {
+        let smoothed = run(
+            Smoothing::Ewma {
+                half_life_secs: 1.0,
+            },
+            &[0.0, 100.0, 100.0],
+            1.0,
+        );
+
+        assert_approx_eq!(smoothed[0], 0.0);
+        assert_approx_eq!(smoothed[1], 50.0);
+        assert_approx_eq!(smoothed[2], 75.0);
+    }
+
+    #[test]
+    fn test_window_mean_drops_old_samples() {
+        let smoothed = run(
+            Smoothing::Window { window_secs: 1.0 },
+            &[10.0, 20.0, 30.0, 40.0],
+            0.5,
+        );
+
+        assert_eq!(smoothed, vec![10.0, 15.0, 25.0, 35.0]);
+    }
+
+    #[test]
+    fn test_empty_window_passes_values_through() {
+        for window_secs in [0.0, -1.0] {
+            let smoothed = run(Smoothing::Window { window_secs }, &[10.0, 20.0, 30.0], 0.5);
+            assert_eq!(smoothed, vec![10.0, 20.0, 30.0]);
+        }
+    }
+
+    #[test]
+    fn test_missing_key_restarts_history() {
+        let mut smoother = RateSmoother::new(Smoothing::Ewma {
+            half_life_secs: 10.0,
+        });
+        smoother.smooth("sda", 1000.0, 1.0);
+        smoother.finish();
+        smoother.finish();
+
+        assert_eq!(smoother.smooth("sda", 10.0, 1.0), 10.0);
+    }
+
+    #[test]
+    fn test_smoothed_rates_keep_raw_value() {
+        let mut rates = SmoothedRates::new(Smoothing::Ewma {
+            half_life_secs: 1.0,
+        });
+        rates.rate("eth0", 0, 1.0);
+        rates.finish();
+        rates.rate("eth0", 100, 1.0);
+        rates.finish();
+
+        let (raw, smoothed) = rates.rate("eth0", 400, 1.0);
+        assert_approx_eq!(raw, 300.0);
+        assert_approx_eq!(smoothed, 200.0);
+    }
+
+    #[test]
+    fn test_smoothing_starts_from_first_measured_rate() {
+        let mut rates = SmoothedRates::new(Smoothing::Ewma {
+            half_life_secs: 10.0,
+        });
+        assert_eq!(rates.rate("eth0", 5000, 1.0), (0.0, 0.0));
+        rates.finish();
+
+        assert_eq!(rates.rate("eth0", 6000, 1.0), (1000.0, 1000.0));
+    }
+
+    #[test]
+    fn test_restart_smoothing_forgets_history() {
+        let mut rates = SmoothedRates::new(Smoothing::Ewma {
+            half_life_secs: 10.0,
+        });
+        for value in [0, 1000, 2000] {
+            rates.rate("eth0", value, 1.0);
+            rates.finish();
+        }
+
+        rates.restart_smoothing();
+        assert_eq!(rates.rate("eth0", 2100, 1.0), (100.0, 100.0));
+    }
+}
@@ -0,0 +1,113 @@
+use crate::models::{SystemMetrics, UnitMetrics};
+use crate::services::CgroupReader;
+use crate::services::collectors::{CollectContext, CounterRates, MetricCollector};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Breaks resource use down by child cgroup of a configured parent, so load
+/// can be pinned on a particular service. Only registered while the unit
+/// section is shown.
+pub struct UnitCollector {
+    reader: CgroupReader,
+    parent: String,
+    previous_cpu: HashMap<String, Duration>,
+    io_read: CounterRates<String>,
+    io_written: CounterRates<String>,
+}
+
+impl UnitCollector {
+    pub fn new(reader: CgroupReader, parent: impl Into<String>) -> Self {
+        Self {
+            reader,
+            parent: parent.into(),
+            previous_cpu: HashMap::new(),
+            io_read: CounterRates::new(),
+            io_written: CounterRates::new(),
+        }
+    }
+}
+
+impl MetricCollector for UnitCollector {
+    fn name(&self) -> &'static str {
+        "units"
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, metrics: &mut SystemMetrics) {
+        let current = self.reader.units(&self.parent);
+
+        metrics.units = current
+            .iter()
+            .map(|unit| {
+                // A unit seen for the first time has nothing to diff against,
+                // so it shows no activity until the next sample. CPU time is
+                // a 64-bit duration that can't wrap, so it doesn't need the
+                // counter tracker's wraparound handling.
+                let previous_cpu = self.previous_cpu.get(&unit.name).unwrap_or(&unit.cpu_usage);
+                let cpu_secs = unit.cpu_usage.saturating_sub(*previous_cpu).as_secs_f64();
+
+                UnitMetrics {
+                    name: unit.name.clone(),
+                    cpu_percentage: if ctx.elapsed_secs > 0.0 {
+                        (cpu_secs / ctx.elapsed_secs * 100.0) as f32
+                    } else {
+                        0.0
+                    },
+                    memory_bytes: unit.memory_current_bytes,
+                    io_read_rate: self.io_read.rate(
+                        unit.name.clone(),
+                        unit.io_read_bytes,
+                        ctx.elapsed_secs,
+                    ),
+                    io_write_rate: self.io_written.rate(
+                        unit.name.clone(),
+                        unit.io_written_bytes,
+                        ctx.elapsed_secs,
+                    ),
+                }
+            })
+            .collect();
+        self.io_read.finish();
+        self.io_written.finish();
+
+        self.previous_cpu = current
+            .into_iter()
+            .map(|unit| (unit.name, unit.cpu_usage))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::sysfs::write_fixture;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_unit_rates_between_samples() {
+        let cgroup = tempdir().unwrap();
+        let unit = cgroup.path().join("system.slice/nginx.service");
+        write_fixture(&unit.join("cpu.stat"), "usage_usec 1000000");
+        write_fixture(&unit.join("memory.current"), "2048");
+        write_fixture(&unit.join("io.stat"), "8:0 rbytes=1000 wbytes=0");
+
+        let reader = CgroupReader::with_roots(cgroup.path(), cgroup.path());
+        let mut collector = UnitCollector::new(reader, "system.slice");
+        let mut metrics = SystemMetrics::zero();
+        let ctx = CollectContext { elapsed_secs: 2.0 };
+
+        collector.collect(&ctx, &mut metrics);
+        assert_eq!(metrics.units[0].cpu_percentage, 0.0);
+        assert_eq!(metrics.units[0].io_read_rate, 0.0);
+
+        write_fixture(&unit.join("cpu.stat"), "usage_usec 2000000");
+        write_fixture(&unit.join("io.stat"), "8:0 rbytes=5000 wbytes=600");
+        collector.collect(&ctx, &mut metrics);
+
+        let nginx = &metrics.units[0];
+        assert_eq!(nginx.name, "nginx.service");
+        assert_eq!(nginx.cpu_percentage, 50.0);
+        assert_eq!(nginx.memory_bytes, 2048);
+        assert_eq!(nginx.io_read_rate, 2000.0);
+        assert_eq!(nginx.io_write_rate, 300.0);
+    }
+}
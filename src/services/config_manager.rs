@@ -80,4 +80,20 @@ mod tests {
         manager.save(&config).unwrap();
         assert!(path.exists());
     }
+
+    #[test]
+    fn test_load_fills_missing_fields_with_defaults() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "transparency = 0.5\n").unwrap();
+
+        let manager = ConfigManager::new(path);
+        let config = manager.load().unwrap();
+
+        assert_eq!(config.transparency, 0.5);
+        assert_eq!(
+            config.refresh_interval_secs,
+            UserConfig::default().refresh_interval_secs
+        );
+    }
 }
@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+/// CPU time consumed so far by the calling thread. Sampling runs on a single
+/// thread, so the difference across `SystemMonitor::collect` is perch's own
+/// cost for that sample.
+#[cfg(unix)]
+pub fn thread_cpu_time() -> Option<Duration> {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+
+    // SAFETY: `ts` is a valid, writable timespec for the duration of the call.
+    let result = unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut ts) };
+    if result != 0 {
+        return None;
+    }
+
+    Some(Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32))
+}
+
+#[cfg(not(unix))]
+pub fn thread_cpu_time() -> Option<Duration> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_cpu_time_advances_with_work() {
+        let before = thread_cpu_time().unwrap();
+
+        let mut acc = 0u64;
+        for i in 0..5_000_000u64 {
+            acc = acc.wrapping_add(i * i);
+        }
+        std::hint::black_box(acc);
+
+        let after = thread_cpu_time().unwrap();
+        assert!(after > before);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::models::CoreKind;
+use crate::services::sysfs::{read_parsed, read_trimmed};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoreTopology {
+    pub package_id: u32,
+    pub core_id: u32,
+    pub kind: CoreKind,
+    pub min_frequency_mhz: u64,
+    pub max_frequency_mhz: u64,
+}
+
+pub struct CpuTopologyReader {
+    sysfs_root: PathBuf,
+}
+
+impl CpuTopologyReader {
+    pub fn new() -> Self {
+        Self::with_root("/sys")
+    }
+
+    pub fn with_root(sysfs_root: impl Into<PathBuf>) -> Self {
+        Self {
+            sysfs_root: sysfs_root.into(),
+        }
+    }
+
+    fn cpu_dir(&self, cpu: usize) -> PathBuf {
+        self.sysfs_root
+            .join("devices/system/cpu")
+            .join(format!("cpu{}", cpu))
+    }
+
+    /// Reads the static layout of each logical CPU. This doesn't change while
+    /// perch is running, so it only needs reading once.
+    pub fn read(&self, cpu_count: usize) -> Vec<Option<CoreTopology>> {
+        let kinds = self.core_kinds(cpu_count);
+
+        (0..cpu_count)
+            .map(|cpu| {
+                let dir = self.cpu_dir(cpu);
+                let topology = dir.join("topology");
+
+                Some(CoreTopology {
+                    package_id: read_parsed(&topology.join("physical_package_id"))?,
+                    core_id: read_parsed(&topology.join("core_id"))?,
+                    kind: kinds[cpu],
+                    min_frequency_mhz: read_khz(&dir.join("cpufreq/cpuinfo_min_freq")),
+                    max_frequency_mhz: read_khz(&dir.join("cpufreq/cpuinfo_max_freq")),
+                })
+            })
+            .collect()
+    }
+
+    pub fn current_frequency_mhz(&self, cpu: usize) -> Option<u64> {
+        read_parsed::<u64>(&self.cpu_dir(cpu).join("cpufreq/scaling_cur_freq"))
+            .map(|khz| khz / 1000)
+    }
+
+    /// Intel hybrid parts list their P- and E-cores under separate PMU
+    /// devices. Elsewhere (ARM big.LITTLE) the scheduler's `cpu_capacity`
+    /// is lower on the efficiency cores.
+    fn core_kinds(&self, cpu_count: usize) -> Vec<CoreKind> {
+        let devices = self.sysfs_root.join("devices");
+        let performance = read_cpulist(&devices.join("cpu_core/cpus"));
+        let efficiency = read_cpulist(&devices.join("cpu_atom/cpus"));
+
+        if !performance.is_empty() || !efficiency.is_empty() {
+            return (0..cpu_count)
+                .map(|cpu| {
+                    if performance.contains(&cpu) {
+                        CoreKind::Performance
+                    } else if efficiency.contains(&cpu) {
+                        CoreKind::Efficiency
+                    } else {
+                        CoreKind::Unknown
+                    }
+                })
+                .collect();
+        }
+
+        let capacities: Vec<Option<u32>> = (0..cpu_count)
+            .map(|cpu| read_parsed(&self.cpu_dir(cpu).join("cpu_capacity")))
+            .collect();
+        let highest = capacities.iter().flatten().max().copied();
+        let lowest = capacities.iter().flatten().min().copied();
+
+        match (highest, lowest) {
+            (Some(highest), Some(lowest)) if highest != lowest => capacities
+                .iter()
+                .map(|capacity| match capacity {
+                    Some(c) if *c == highest => CoreKind::Performance,
+                    Some(_) => CoreKind::Efficiency,
+                    None => CoreKind::Unknown,
+                })
+                .collect(),
+            _ => vec![CoreKind::Unknown; cpu_count],
+        }
+    }
+}
+
+impl Default for CpuTopologyReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn read_khz(path: &Path) -> u64 {
+    read_parsed::<u64>(path).map(|khz| khz / 1000).unwrap_or(0)
+}
+
+fn read_cpulist(path: &Path) -> HashSet<usize> {
+    read_trimmed(path)
+        .map(|list| parse_cpulist(&list))
+        .unwrap_or_default()
+}
+
+/// Parses the kernel's cpulist format, e.g. `0-3,8,10-11`.
+pub fn parse_cpulist(list: &str) -> HashSet<usize> {
+    list.split(',')
+        .filter_map(|range| match range.trim().split_once('-') {
+            Some((start, end)) => Some(start.parse().ok()?..=end.parse().ok()?),
+            None => {
+                let cpu = range.trim().parse().ok()?;
+                Some(cpu..=cpu)
+            }
+        })
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::sysfs::write_fixture;
+    use tempfile::tempdir;
+
+    fn add_cpu(root: &Path, cpu: usize, core_id: u32, max_khz: u64) {
+        let dir = root.join(format!("devices/system/cpu/cpu{}", cpu));
+        write_fixture(&dir.join("topology/physical_package_id"), "0");
+        write_fixture(&dir.join("topology/core_id"), &core_id.to_string());
+        write_fixture(&dir.join("cpufreq/cpuinfo_min_freq"), "800000");
+        write_fixture(&dir.join("cpufreq/cpuinfo_max_freq"), &max_khz.to_string());
+        write_fixture(&dir.join("cpufreq/scaling_cur_freq"), "2400000");
+    }
+
+    #[test]
+    fn test_parse_cpulist() {
+        let cpus = parse_cpulist("0-3,8,10-11");
+        let mut sorted: Vec<usize> = cpus.into_iter().collect();
+        sorted.sort_unstable();
+
+        assert_eq!(sorted, vec![0, 1, 2, 3, 8, 10, 11]);
+        assert!(parse_cpulist("").is_empty());
+    }
+
+    #[test]
+    fn test_reads_intel_hybrid_topology() {
+        let dir = tempdir().unwrap();
+        add_cpu(dir.path(), 0, 0, 5_000_000);
+        add_cpu(dir.path(), 1, 0, 5_000_000);
+        add_cpu(dir.path(), 2, 8, 3_800_000);
+        write_fixture(&dir.path().join("devices/cpu_core/cpus"), "0-1");
+        write_fixture(&dir.path().join("devices/cpu_atom/cpus"), "2");
+
+        let reader = CpuTopologyReader::with_root(dir.path());
+        let topology = reader.read(3);
+
+        let first = topology[0].as_ref().unwrap();
+        assert_eq!(first.kind, CoreKind::Performance);
+        assert_eq!(first.min_frequency_mhz, 800);
+        assert_eq!(first.max_frequency_mhz, 5000);
+        assert_eq!(topology[1].as_ref().unwrap().core_id, 0);
+        assert_eq!(topology[2].as_ref().unwrap().kind, CoreKind::Efficiency);
+        assert_eq!(reader.current_frequency_mhz(2), Some(2400));
+    }
+
+    #[test]
+    fn test_cpu_capacity_marks_little_cores() {
+        let dir = tempdir().unwrap();
+        for (cpu, capacity) in [(0, "1024"), (1, "1024"), (2, "446")] {
+            add_cpu(dir.path(), cpu, cpu as u32, 2_000_000);
+            write_fixture(
+                &dir.path()
+                    .join(format!("devices/system/cpu/cpu{}/cpu_capacity", cpu)),
+                capacity,
+            );
+        }
+
+        let topology = CpuTopologyReader::with_root(dir.path()).read(3);
+
+        assert_eq!(topology[0].as_ref().unwrap().kind, CoreKind::Performance);
+        assert_eq!(topology[2].as_ref().unwrap().kind, CoreKind::Efficiency);
+    }
+
+    #[test]
+    fn test_missing_sysfs_yields_no_topology() {
+        let dir = tempdir().unwrap();
+        let topology = CpuTopologyReader::with_root(dir.path()).read(2);
+
+        assert_eq!(topology, vec![None, None]);
+    }
+}
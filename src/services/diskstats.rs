@@ -0,0 +1,181 @@
+use crate::services::sysfs::links_to_virtual;
+use std::fs;
+use std::path::PathBuf;
+
+// The kernel always reports diskstats sectors in 512-byte units, regardless
+// of the device's logical block size.
+const SECTOR_SIZE: u64 = 512;
+
+const VIRTUAL_PREFIXES: &[&str] = &["loop", "ram", "zram"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiskStat {
+    pub name: String,
+    pub sectors_read: u64,
+    pub sectors_written: u64,
+}
+
+impl DiskStat {
+    pub fn read_bytes(&self) -> u64 {
+        self.sectors_read * SECTOR_SIZE
+    }
+
+    pub fn written_bytes(&self) -> u64 {
+        self.sectors_written * SECTOR_SIZE
+    }
+}
+
+pub struct DiskStatsReader {
+    procfs_root: PathBuf,
+    sysfs_root: PathBuf,
+}
+
+impl DiskStatsReader {
+    pub fn new() -> Self {
+        Self::with_roots("/proc", "/sys")
+    }
+
+    pub fn with_roots(procfs_root: impl Into<PathBuf>, sysfs_root: impl Into<PathBuf>) -> Self {
+        Self {
+            procfs_root: procfs_root.into(),
+            sysfs_root: sysfs_root.into(),
+        }
+    }
+
+    /// Reads `/proc/diskstats` and keeps only whole physical devices, so that
+    /// partitions, loop and ram devices don't count the same I/O twice.
+    pub fn read(&self) -> Vec<DiskStat> {
+        let contents = match fs::read_to_string(self.procfs_root.join("diskstats")) {
+            Ok(contents) => contents,
+            Err(_) => return Vec::new(),
+        };
+
+        parse_diskstats(&contents)
+            .into_iter()
+            .filter(|stat| self.is_physical(&stat.name))
+            .collect()
+    }
+
+    fn is_physical(&self, name: &str) -> bool {
+        if VIRTUAL_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+        {
+            return false;
+        }
+
+        let block_dir = self.sysfs_root.join("block");
+        if !block_dir.is_dir() {
+            return true;
+        }
+
+        // Partitions don't appear under /sys/block, and devices such as
+        // device-mapper or md arrays link into /sys/devices/virtual.
+        let device = block_dir.join(name);
+        if fs::symlink_metadata(&device).is_err() {
+            return false;
+        }
+
+        !links_to_virtual(&device)
+    }
+}
+
+impl Default for DiskStatsReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn parse_diskstats(contents: &str) -> Vec<DiskStat> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 {
+                return None;
+            }
+
+            Some(DiskStat {
+                name: fields[2].to_string(),
+                sectors_read: fields[5].parse().ok()?,
+                sectors_written: fields[9].parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+    use tempfile::tempdir;
+
+    const DISKSTATS: &str = "\
+   7       0 loop0 10 0 80 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+ 259       0 nvme0n1 7008 5237 2000 7915 4463 21089 4000 25055 0 4668 33279
+ 259       1 nvme0n1p1 100 0 1000 10 20 0 3000 5 0 10 15
+ 254       0 dm-0 50 0 500 1 1 0 700 1 0 1 2
+   8       0 sda 1 0 10 0 1 0 20 0 0 0 0
+";
+
+    #[test]
+    fn test_parse_diskstats_reads_sector_columns() {
+        let stats = parse_diskstats(DISKSTATS);
+
+        assert_eq!(stats.len(), 5);
+        assert_eq!(stats[1].name, "nvme0n1");
+        assert_eq!(stats[1].sectors_read, 2000);
+        assert_eq!(stats[1].sectors_written, 4000);
+        assert_eq!(stats[1].read_bytes(), 2000 * 512);
+    }
+
+    #[test]
+    fn test_parse_diskstats_skips_malformed_lines() {
+        let stats = parse_diskstats("garbage\n 8 0 sda 1 0 x 0 1 0 20\n");
+        assert!(stats.is_empty());
+    }
+
+    #[test]
+    fn test_reader_keeps_only_physical_devices() {
+        let dir = tempdir().unwrap();
+        let procfs = dir.path().join("proc");
+        let sysfs = dir.path().join("sys");
+        let block = sysfs.join("block");
+        fs::create_dir_all(&procfs).unwrap();
+        fs::create_dir_all(&block).unwrap();
+        fs::write(procfs.join("diskstats"), DISKSTATS).unwrap();
+
+        symlink(
+            "../devices/pci0000:00/nvme/nvme0/nvme0n1",
+            block.join("nvme0n1"),
+        )
+        .unwrap();
+        symlink("../devices/pci0000:00/ata1/sda", block.join("sda")).unwrap();
+        symlink("../devices/virtual/block/dm-0", block.join("dm-0")).unwrap();
+        symlink("../devices/virtual/block/loop0", block.join("loop0")).unwrap();
+
+        let reader = DiskStatsReader::with_roots(&procfs, &sysfs);
+        let names: Vec<String> = reader.read().into_iter().map(|s| s.name).collect();
+
+        assert_eq!(names, vec!["nvme0n1", "sda"]);
+    }
+
+    #[test]
+    fn test_reader_without_sysfs_filters_by_name() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("diskstats"), DISKSTATS).unwrap();
+
+        let reader = DiskStatsReader::with_roots(dir.path(), dir.path().join("missing"));
+        let names: Vec<String> = reader.read().into_iter().map(|s| s.name).collect();
+
+        assert!(!names.contains(&"loop0".to_string()));
+        assert!(names.contains(&"nvme0n1".to_string()));
+    }
+
+    #[test]
+    fn test_reader_missing_diskstats_returns_empty() {
+        let dir = tempdir().unwrap();
+        let reader = DiskStatsReader::with_roots(dir.path(), dir.path());
+        assert!(reader.read().is_empty());
+    }
+}
@@ -1,3 +1,7 @@
+use crate::models::Metric;
+use chrono::{DateTime, Local, TimeDelta};
+use std::time::{Duration, SystemTime};
+
 pub fn format_bytes(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
     let mut size = bytes as f64;
@@ -15,6 +19,64 @@ pub fn format_rate(bytes_per_sec: u64) -> String {
     format!("{}/s", format_bytes(bytes_per_sec))
 }
 
+pub fn format_duration(duration: Duration) -> String {
+    let total_minutes = duration.as_secs() / 60;
+    let hours = total_minutes / 60;
+    let minutes = total_minutes % 60;
+
+    if hours > 0 {
+        format!("{}h{:02}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+pub fn format_uptime(uptime: Duration) -> String {
+    let delta = TimeDelta::from_std(uptime).unwrap_or(TimeDelta::MAX);
+    let days = delta.num_days();
+    let hours = delta.num_hours() % 24;
+    let minutes = delta.num_minutes() % 60;
+
+    if days > 0 {
+        format!("{}d{}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h{:02}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+pub fn format_boot_time(boot_time: SystemTime) -> String {
+    DateTime::<Local>::from(boot_time)
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
+
+/// A metric's value in the units it's measured in.
+pub fn format_metric(metric: Metric, value: f32) -> String {
+    match metric {
+        Metric::NetworkReceived
+        | Metric::NetworkTransmitted
+        | Metric::DiskRead
+        | Metric::DiskWrite => format_rate(value as u64),
+        Metric::LoadOne => format!("{:.2}", value),
+        Metric::Temperature => format!("{:.0}°C", value),
+        Metric::CpuUsage
+        | Metric::MemoryUsage
+        | Metric::SwapUsage
+        | Metric::BatteryCharge
+        | Metric::CpuPressure
+        | Metric::MemoryPressure
+        | Metric::IoPressure
+        | Metric::CoreUsage(_) => format!("{:.0}%", value),
+    }
+}
+
+/// Local wall-clock time of day, for chart axes.
+pub fn format_clock_time(time: SystemTime) -> String {
+    DateTime::<Local>::from(time).format("%H:%M:%S").to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -47,4 +109,35 @@ mod tests {
         assert_eq!(format_rate(1024), "1.0 KB/s");
         assert_eq!(format_rate(1048576), "1.0 MB/s");
     }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(45 * 60)), "45m");
+        assert_eq!(
+            format_duration(Duration::from_secs(2 * 3600 + 5 * 60)),
+            "2h05m"
+        );
+        assert_eq!(format_duration(Duration::from_secs(30)), "0m");
+    }
+
+    #[test]
+    fn test_format_metric() {
+        assert_eq!(format_metric(Metric::CpuUsage, 93.4), "93%");
+        assert_eq!(format_metric(Metric::NetworkReceived, 2048.0), "2.0 KB/s");
+        assert_eq!(format_metric(Metric::LoadOne, 1.5), "1.50");
+        assert_eq!(format_metric(Metric::Temperature, 71.6), "72°C");
+    }
+
+    #[test]
+    fn test_format_uptime() {
+        assert_eq!(format_uptime(Duration::from_secs(12 * 60)), "12m");
+        assert_eq!(
+            format_uptime(Duration::from_secs(3 * 3600 + 7 * 60)),
+            "3h07m"
+        );
+        assert_eq!(
+            format_uptime(Duration::from_secs(2 * 86400 + 5 * 3600 + 59)),
+            "2d5h"
+        );
+    }
 }
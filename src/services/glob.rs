@@ -0,0 +1,53 @@
+/// Shell-style matching where `*` matches any run of characters (including
+/// `/`) and `?` matches exactly one character.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match_literals() {
+        assert!(glob_match("/home", "/home"));
+        assert!(!glob_match("/home", "/home/user"));
+    }
+
+    #[test]
+    fn test_glob_match_wildcards() {
+        assert!(glob_match("/snap/*", "/snap/core/1234"));
+        assert!(glob_match("/media/*/disk?", "/media/usb/disk1"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*boot*", "/boot/efi"));
+        assert!(!glob_match("/media/*/disk?", "/media/usb/disk10"));
+        assert!(!glob_match("/run/*", "/var/run"));
+    }
+}
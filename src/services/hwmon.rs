@@ -0,0 +1,150 @@
+use crate::models::{FanSensor, TemperatureSensor, ThermalMetrics};
+use crate::services::sysfs::{read_parsed, read_trimmed};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct HwmonReader {
+    sysfs_root: PathBuf,
+}
+
+impl HwmonReader {
+    pub fn new() -> Self {
+        Self::with_root("/sys")
+    }
+
+    pub fn with_root(sysfs_root: impl Into<PathBuf>) -> Self {
+        Self {
+            sysfs_root: sysfs_root.into(),
+        }
+    }
+
+    /// Walks `/sys/class/hwmon/hwmon*`, reading every `tempN_*` and `fanN_*`
+    /// channel. The kernel reports temperatures in millidegrees Celsius.
+    pub fn read(&self) -> ThermalMetrics {
+        let mut thermal = ThermalMetrics::zero();
+
+        let mut chips: Vec<PathBuf> =
+            match fs::read_dir(self.sysfs_root.join("class").join("hwmon")) {
+                Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).collect(),
+                Err(_) => return thermal,
+            };
+        chips.sort();
+
+        for chip in chips {
+            let chip_name = read_trimmed(&chip.join("name")).unwrap_or_default();
+
+            for index in channel_indices(&chip, "temp", "_input") {
+                let Some(millidegrees) =
+                    read_parsed::<i64>(&chip.join(format!("temp{}_input", index)))
+                else {
+                    continue;
+                };
+
+                thermal.sensors.push(TemperatureSensor {
+                    label: channel_label(&chip, &chip_name, "temp", index),
+                    current_celsius: millidegrees as f32 / 1000.0,
+                    max_celsius: read_millidegrees(&chip, index, "max"),
+                    critical_celsius: read_millidegrees(&chip, index, "crit"),
+                });
+            }
+
+            for index in channel_indices(&chip, "fan", "_input") {
+                let Some(rpm) = read_parsed::<u32>(&chip.join(format!("fan{}_input", index)))
+                else {
+                    continue;
+                };
+
+                thermal.fans.push(FanSensor {
+                    label: channel_label(&chip, &chip_name, "fan", index),
+                    rpm,
+                });
+            }
+        }
+
+        thermal
+    }
+}
+
+impl Default for HwmonReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn channel_indices(chip: &Path, prefix: &str, suffix: &str) -> Vec<u32> {
+    let mut indices: Vec<u32> = fs::read_dir(chip)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    let name = e.file_name().to_string_lossy().into_owned();
+                    name.strip_prefix(prefix)?
+                        .strip_suffix(suffix)?
+                        .parse()
+                        .ok()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    indices.sort_unstable();
+    indices
+}
+
+fn channel_label(chip: &Path, chip_name: &str, prefix: &str, index: u32) -> String {
+    read_trimmed(&chip.join(format!("{}{}_label", prefix, index)))
+        .unwrap_or_else(|| format!("{} {}{}", chip_name, prefix, index))
+}
+
+fn read_millidegrees(chip: &Path, index: u32, kind: &str) -> Option<f32> {
+    read_parsed::<i64>(&chip.join(format!("temp{}_{}", index, kind)))
+        .filter(|&value| value > 0)
+        .map(|value| value as f32 / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::sysfs::write_fixture;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_reads_temperatures_and_fans_from_fixture() {
+        let dir = tempdir().unwrap();
+        let coretemp = dir.path().join("class/hwmon/hwmon0");
+        let fans = dir.path().join("class/hwmon/hwmon1");
+
+        write_fixture(&coretemp.join("name"), "coretemp");
+        write_fixture(&coretemp.join("temp1_input"), "52000");
+        write_fixture(&coretemp.join("temp1_label"), "Package id 0");
+        write_fixture(&coretemp.join("temp1_max"), "80000");
+        write_fixture(&coretemp.join("temp1_crit"), "100000");
+        write_fixture(&coretemp.join("temp2_input"), "48500");
+
+        write_fixture(&fans.join("name"), "thinkpad");
+        write_fixture(&fans.join("fan1_input"), "2400");
+
+        let thermal = HwmonReader::with_root(dir.path()).read();
+
+        assert_eq!(thermal.sensors.len(), 2);
+        assert_eq!(thermal.sensors[0].label, "Package id 0");
+        assert_eq!(thermal.sensors[0].current_celsius, 52.0);
+        assert_eq!(thermal.sensors[0].max_celsius, Some(80.0));
+        assert_eq!(thermal.sensors[0].critical_celsius, Some(100.0));
+        assert_eq!(thermal.sensors[1].label, "coretemp temp2");
+        assert_eq!(thermal.sensors[1].max_celsius, None);
+
+        assert_eq!(thermal.fans.len(), 1);
+        assert_eq!(thermal.fans[0].label, "thinkpad fan1");
+        assert_eq!(thermal.fans[0].rpm, 2400);
+    }
+
+    #[test]
+    fn test_missing_hwmon_is_empty() {
+        let dir = tempdir().unwrap();
+        let thermal = HwmonReader::with_root(dir.path()).read();
+
+        assert!(thermal.sensors.is_empty());
+        assert!(thermal.fans.is_empty());
+    }
+}
@@ -0,0 +1,169 @@
+use crate::models::MemoryBreakdown;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct MeminfoReader {
+    procfs_root: PathBuf,
+    sysfs_root: PathBuf,
+}
+
+impl MeminfoReader {
+    pub fn new() -> Self {
+        Self::with_roots("/proc", "/sys")
+    }
+
+    pub fn with_roots(procfs_root: impl Into<PathBuf>, sysfs_root: impl Into<PathBuf>) -> Self {
+        Self {
+            procfs_root: procfs_root.into(),
+            sysfs_root: sysfs_root.into(),
+        }
+    }
+
+    /// Reads `/proc/meminfo`, plus the compression stats of any zram devices
+    /// since meminfo only covers zswap. Returns `None` without procfs.
+    pub fn read(&self) -> Option<MemoryBreakdown> {
+        let contents = fs::read_to_string(self.procfs_root.join("meminfo")).ok()?;
+        let fields = parse_meminfo(&contents);
+        let field = |name: &str| fields.get(name).copied().unwrap_or(0);
+
+        // Hugepage counts are in pages rather than kB.
+        let hugepage_size = field("Hugepagesize");
+        let (zram_original_bytes, zram_compressed_bytes) = self.read_zram();
+
+        Some(MemoryBreakdown {
+            free_bytes: field("MemFree"),
+            buffers_bytes: field("Buffers"),
+            cached_bytes: field("Cached"),
+            shared_bytes: field("Shmem"),
+            slab_reclaimable_bytes: field("SReclaimable"),
+            slab_unreclaimable_bytes: field("SUnreclaim"),
+            dirty_bytes: field("Dirty"),
+            writeback_bytes: field("Writeback"),
+            hugepages_total_bytes: field("HugePages_Total") * hugepage_size,
+            hugepages_free_bytes: field("HugePages_Free") * hugepage_size,
+            zswap_compressed_bytes: field("Zswap"),
+            zswap_original_bytes: field("Zswapped"),
+            zram_compressed_bytes,
+            zram_original_bytes,
+        })
+    }
+
+    /// Sums `orig_data_size` and `compr_data_size`, the first two columns of
+    /// each `/sys/block/zram*/mm_stat`.
+    fn read_zram(&self) -> (u64, u64) {
+        let entries = match fs::read_dir(self.sysfs_root.join("block")) {
+            Ok(entries) => entries,
+            Err(_) => return (0, 0),
+        };
+
+        entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().starts_with("zram"))
+            .filter_map(|e| read_mm_stat(&e.path().join("mm_stat")))
+            .fold((0, 0), |(original, compressed), (o, c)| {
+                (original + o, compressed + c)
+            })
+    }
+}
+
+impl Default for MeminfoReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn read_mm_stat(path: &Path) -> Option<(u64, u64)> {
+    let contents = fs::read_to_string(path).ok()?;
+    let mut fields = contents.split_whitespace().map(|f| f.parse::<u64>().ok());
+
+    Some((fields.next()??, fields.next()??))
+}
+
+/// Parses `Name:   1234 kB` lines into bytes. Lines without a unit (the
+/// hugepage counts) are kept as plain numbers.
+pub fn parse_meminfo(contents: &str) -> HashMap<String, u64> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (name, rest) = line.split_once(':')?;
+            let mut parts = rest.split_whitespace();
+            let value: u64 = parts.next()?.parse().ok()?;
+            let value = match parts.next() {
+                Some("kB") => value * 1024,
+                _ => value,
+            };
+
+            Some((name.to_string(), value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const MEMINFO: &str = "\
+MemTotal:        1000 kB
+MemFree:          200 kB
+MemAvailable:     500 kB
+Buffers:           50 kB
+Cached:           300 kB
+Shmem:             40 kB
+SReclaimable:      60 kB
+SUnreclaim:        20 kB
+Dirty:              8 kB
+Writeback:          2 kB
+Zswap:             10 kB
+Zswapped:          30 kB
+HugePages_Total:    4
+HugePages_Free:     1
+Hugepagesize:    2048 kB
+";
+
+    #[test]
+    fn test_parse_meminfo_converts_units() {
+        let fields = parse_meminfo(MEMINFO);
+
+        assert_eq!(fields["MemTotal"], 1000 * 1024);
+        assert_eq!(fields["HugePages_Total"], 4);
+        assert!(!fields.contains_key("garbage"));
+    }
+
+    #[test]
+    fn test_reader_builds_breakdown_with_zram() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("meminfo"), MEMINFO).unwrap();
+        fs::create_dir_all(dir.path().join("block/zram0")).unwrap();
+        fs::create_dir_all(dir.path().join("block/sda")).unwrap();
+        fs::write(
+            dir.path().join("block/zram0/mm_stat"),
+            "4096000 1024000 1200000 0 1200000 12 0 0 0\n",
+        )
+        .unwrap();
+
+        let breakdown = MeminfoReader::with_roots(dir.path(), dir.path())
+            .read()
+            .unwrap();
+
+        assert_eq!(breakdown.free_bytes, 200 * 1024);
+        assert_eq!(breakdown.shared_bytes, 40 * 1024);
+        assert_eq!(breakdown.dirty_bytes, 8 * 1024);
+        assert_eq!(breakdown.hugepages_total_bytes, 4 * 2048 * 1024);
+        assert_eq!(breakdown.hugepages_free_bytes, 2048 * 1024);
+        assert_eq!(breakdown.zswap_original_bytes, 30 * 1024);
+        assert_eq!(breakdown.zram_original_bytes, 4_096_000);
+        assert_eq!(breakdown.zram_compressed_bytes, 1_024_000);
+    }
+
+    #[test]
+    fn test_reader_without_procfs() {
+        let dir = tempdir().unwrap();
+        assert!(
+            MeminfoReader::with_roots(dir.path(), dir.path())
+                .read()
+                .is_none()
+        );
+    }
+}
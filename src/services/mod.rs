@@ -1,7 +1,45 @@
+pub mod alert_commands;
+pub mod alerts;
+pub mod cgroup;
+pub mod clock;
+pub mod collectors;
 pub mod config_manager;
+pub mod cpu_time;
+pub mod cpu_topology;
+pub mod diskstats;
 pub mod format;
+pub mod glob;
+pub mod hwmon;
+pub mod meminfo;
+pub mod mounts;
+pub mod netdev;
+pub mod notifications;
+pub mod power_supply;
+pub mod pressure;
+pub mod proc_counts;
+pub mod proc_stat;
+pub mod sampler;
+pub mod sysfs;
 pub mod system_monitor;
 
+pub use alert_commands::CommandRunner;
+pub use alerts::{AlertEngine, RuleStatus};
+pub use cgroup::CgroupReader;
+pub use clock::{Clock, SystemClock};
 pub use config_manager::ConfigManager;
-pub use format::{format_bytes, format_rate};
+pub use cpu_topology::CpuTopologyReader;
+pub use diskstats::DiskStatsReader;
+pub use format::{
+    format_boot_time, format_bytes, format_clock_time, format_duration, format_metric, format_rate,
+    format_uptime,
+};
+pub use hwmon::HwmonReader;
+pub use meminfo::MeminfoReader;
+pub use mounts::{MountFilter, MountReader};
+pub use netdev::NetInterfaceReader;
+pub use notifications::Notifier;
+pub use power_supply::PowerSupplyReader;
+pub use pressure::PressureReader;
+pub use proc_stat::ProcStatReader;
+pub use sampler::Sampler;
 pub use system_monitor::SystemMonitor;
@@ -0,0 +1,298 @@
+use crate::models::FilesystemMetrics;
+use crate::services::glob::glob_match;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Filesystem types that don't represent real storage. These are hidden
+/// unless a mount point is explicitly listed in the include globs.
+const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "autofs",
+    "binfmt_misc",
+    "bpf",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devpts",
+    "devtmpfs",
+    "efivarfs",
+    "fusectl",
+    "hugetlbfs",
+    "mqueue",
+    "nsfs",
+    "overlay",
+    "proc",
+    "pstore",
+    "ramfs",
+    "rpc_pipefs",
+    "securityfs",
+    "squashfs",
+    "sysfs",
+    "tmpfs",
+    "tracefs",
+];
+
+/// Filesystems served over the network. `statvfs` on one whose server has
+/// gone away can block for minutes, holding up every other collector, so
+/// these are hidden unless explicitly included too.
+const NETWORK_FILESYSTEMS: &[&str] = &[
+    "9p",
+    "afs",
+    "ceph",
+    "cifs",
+    "davfs",
+    "fuse.rclone",
+    "fuse.sshfs",
+    "glusterfs",
+    "ncpfs",
+    "nfs",
+    "nfs4",
+    "smb3",
+    "smbfs",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MountEntry {
+    pub device: String,
+    pub mount_point: String,
+    pub fs_type: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MountFilter {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl MountFilter {
+    /// Exclude globs always win. With no include globs every local, real
+    /// filesystem is shown; otherwise only mounts matching an include glob
+    /// are, which is also how a pseudo filesystem such as a tmpfs or a
+    /// network share can be opted in.
+    pub fn allows(&self, entry: &MountEntry) -> bool {
+        let matches = |globs: &[String]| globs.iter().any(|g| glob_match(g, &entry.mount_point));
+
+        if matches(&self.exclude) {
+            return false;
+        }
+
+        if self.include.is_empty() {
+            let fs_type = entry.fs_type.as_str();
+            !PSEUDO_FILESYSTEMS.contains(&fs_type) && !NETWORK_FILESYSTEMS.contains(&fs_type)
+        } else {
+            matches(&self.include)
+        }
+    }
+}
+
+pub struct MountReader {
+    procfs_root: PathBuf,
+}
+
+impl MountReader {
+    pub fn new() -> Self {
+        Self::with_root("/proc")
+    }
+
+    pub fn with_root(procfs_root: impl Into<PathBuf>) -> Self {
+        Self {
+            procfs_root: procfs_root.into(),
+        }
+    }
+
+    pub fn mounts(&self, filter: &MountFilter) -> Vec<MountEntry> {
+        let contents = match fs::read_to_string(self.procfs_root.join("mounts")) {
+            Ok(contents) => contents,
+            Err(_) => return Vec::new(),
+        };
+
+        // Bind mounts and containers often mount the same device several
+        // times; only the first mount point is reported.
+        let mut seen_devices = HashSet::new();
+        parse_mounts(&contents)
+            .into_iter()
+            .filter(|entry| filter.allows(entry))
+            .filter(|entry| {
+                !entry.device.starts_with('/') || seen_devices.insert(entry.device.clone())
+            })
+            .collect()
+    }
+
+    pub fn read(&self, filter: &MountFilter) -> Vec<FilesystemMetrics> {
+        self.mounts(filter)
+            .into_iter()
+            .filter_map(|entry| {
+                let usage = statvfs(Path::new(&entry.mount_point))?;
+                Some(FilesystemMetrics {
+                    mount_point: entry.mount_point,
+                    device: entry.device,
+                    fs_type: entry.fs_type,
+                    ..usage
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for MountReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn parse_mounts(contents: &str) -> Vec<MountEntry> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some(MountEntry {
+                device: unescape(fields.next()?),
+                mount_point: unescape(fields.next()?),
+                fs_type: fields.next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// `/proc/mounts` writes spaces, tabs and backslashes in paths as
+/// three-digit octal escapes such as `\040`.
+fn unescape(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut chars = field.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            let digits: String = chars.clone().take(3).collect();
+            if let Ok(code) = u8::from_str_radix(&digits, 8) {
+                if digits.len() == 3 {
+                    out.push(code as char);
+                    chars.nth(2);
+                    continue;
+                }
+            }
+        }
+        out.push(c);
+    }
+
+    out
+}
+
+#[cfg(unix)]
+fn statvfs(path: &Path) -> Option<FilesystemMetrics> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    // SAFETY: `stat` is zero-initialised plain data, and `c_path` is a valid
+    // NUL-terminated string that outlives the call.
+    let stat = unsafe {
+        let mut stat: libc::statvfs = std::mem::zeroed();
+        if libc::statvfs(c_path.as_ptr(), &mut stat) != 0 {
+            return None;
+        }
+        stat
+    };
+
+    let fragment = stat.f_frsize as u64;
+    let total_bytes = stat.f_blocks as u64 * fragment;
+    let free_bytes = stat.f_bfree as u64 * fragment;
+
+    Some(FilesystemMetrics {
+        mount_point: String::new(),
+        device: String::new(),
+        fs_type: String::new(),
+        total_bytes,
+        used_bytes: total_bytes.saturating_sub(free_bytes),
+        available_bytes: stat.f_bavail as u64 * fragment,
+        total_inodes: stat.f_files as u64,
+        used_inodes: (stat.f_files as u64).saturating_sub(stat.f_ffree as u64),
+    })
+}
+
+#[cfg(not(unix))]
+fn statvfs(_path: &Path) -> Option<FilesystemMetrics> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const MOUNTS: &str = "\
+proc /proc proc rw,relatime 0 0
+/dev/nvme0n1p2 / ext4 rw,relatime 0 0
+tmpfs /run tmpfs rw,nosuid 0 0
+/dev/nvme0n1p1 /boot/efi vfat rw,relatime 0 0
+/dev/nvme0n1p2 /var/lib/docker ext4 rw,relatime 0 0
+/dev/loop3 /snap/core/1234 squashfs ro 0 0
+/dev/sdb1 /media/My\\040Disk ext4 rw 0 0
+overlay /var/lib/docker/overlay2/abc/merged overlay rw 0 0
+nas:/export/home /mnt/home nfs4 rw,relatime 0 0
+//nas/media /mnt/media cifs rw,relatime 0 0
+";
+
+    fn mount_points(entries: &[MountEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.mount_point.as_str()).collect()
+    }
+
+    fn reader_with(contents: &str) -> (tempfile::TempDir, MountReader) {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("mounts"), contents).unwrap();
+        let reader = MountReader::with_root(dir.path());
+        (dir, reader)
+    }
+
+    #[test]
+    fn test_parse_mounts_unescapes_paths() {
+        let entries = parse_mounts(MOUNTS);
+        assert_eq!(entries[6].mount_point, "/media/My Disk");
+        assert_eq!(entries[6].fs_type, "ext4");
+    }
+
+    #[test]
+    fn test_default_filter_drops_pseudo_and_duplicate_devices() {
+        let (_dir, reader) = reader_with(MOUNTS);
+        let entries = reader.mounts(&MountFilter::default());
+
+        assert_eq!(
+            mount_points(&entries),
+            vec!["/", "/boot/efi", "/media/My Disk"]
+        );
+    }
+
+    #[test]
+    fn test_include_and_exclude_globs() {
+        let (_dir, reader) = reader_with(MOUNTS);
+        let filter = MountFilter {
+            include: vec!["/".to_string(), "/run".to_string(), "/media/*".to_string()],
+            exclude: vec!["/media/*".to_string()],
+        };
+
+        let entries = reader.mounts(&filter);
+        assert_eq!(mount_points(&entries), vec!["/", "/run"]);
+    }
+
+    #[test]
+    fn test_network_filesystems_only_when_included() {
+        let (_dir, reader) = reader_with(MOUNTS);
+        let filter = MountFilter {
+            include: vec!["/mnt/home".to_string()],
+            exclude: Vec::new(),
+        };
+
+        let entries = reader.mounts(&filter);
+        assert_eq!(mount_points(&entries), vec!["/mnt/home"]);
+    }
+
+    #[test]
+    fn test_statvfs_reports_capacity() {
+        let dir = tempdir().unwrap();
+        let usage = statvfs(dir.path()).unwrap();
+
+        assert!(usage.total_bytes > 0);
+        assert!(usage.used_bytes <= usage.total_bytes);
+        assert!(usage.available_bytes <= usage.total_bytes);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::services::sysfs::{links_to_virtual, read_trimmed};
+use std::path::PathBuf;
+
+// Used when /sys/class/net isn't available to tell us which interfaces are
+// software-only.
+const VIRTUAL_PREFIXES: &[&str] = &["lo", "docker", "veth", "br-", "virbr", "cni", "flannel"];
+
+pub struct NetInterfaceReader {
+    sysfs_root: PathBuf,
+}
+
+impl NetInterfaceReader {
+    pub fn new() -> Self {
+        Self::with_root("/sys")
+    }
+
+    pub fn with_root(sysfs_root: impl Into<PathBuf>) -> Self {
+        Self {
+            sysfs_root: sysfs_root.into(),
+        }
+    }
+
+    fn class_dir(&self) -> PathBuf {
+        self.sysfs_root.join("class").join("net")
+    }
+
+    /// Interfaces that don't report carrier state (loopback, some tunnels)
+    /// show up as "unknown", which we treat as up.
+    pub fn is_up(&self, name: &str) -> bool {
+        match read_trimmed(&self.class_dir().join(name).join("operstate")) {
+            Some(state) => state == "up" || state == "unknown",
+            None => true,
+        }
+    }
+
+    pub fn is_virtual(&self, name: &str) -> bool {
+        let class_dir = self.class_dir();
+        if class_dir.is_dir() {
+            links_to_virtual(&class_dir.join(name))
+        } else {
+            VIRTUAL_PREFIXES
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+        }
+    }
+}
+
+impl Default for NetInterfaceReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::symlink;
+    use tempfile::tempdir;
+
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        let class = dir.path().join("class").join("net");
+        let devices = dir.path().join("devices");
+
+        for (name, parent, state) in [
+            ("lo", "virtual/net", "unknown"),
+            ("docker0", "virtual/net", "down"),
+            ("eth0", "pci0000:00/net", "up"),
+            ("wlan0", "pci0000:00/net", "down"),
+        ] {
+            let device = devices.join(parent).join(name);
+            fs::create_dir_all(&device).unwrap();
+            fs::write(device.join("operstate"), format!("{}\n", state)).unwrap();
+            fs::create_dir_all(&class).unwrap();
+            symlink(&device, class.join(name)).unwrap();
+        }
+
+        dir
+    }
+
+    #[test]
+    fn test_is_virtual_follows_sysfs_links() {
+        let dir = fixture();
+        let reader = NetInterfaceReader::with_root(dir.path());
+
+        assert!(reader.is_virtual("lo"));
+        assert!(reader.is_virtual("docker0"));
+        assert!(!reader.is_virtual("eth0"));
+    }
+
+    #[test]
+    fn test_is_up_reads_operstate() {
+        let dir = fixture();
+        let reader = NetInterfaceReader::with_root(dir.path());
+
+        assert!(reader.is_up("lo"));
+        assert!(reader.is_up("eth0"));
+        assert!(!reader.is_up("wlan0"));
+    }
+
+    #[test]
+    fn test_without_sysfs_falls_back_to_names() {
+        let dir = tempdir().unwrap();
+        let reader = NetInterfaceReader::with_root(dir.path());
+
+        assert!(reader.is_virtual("lo"));
+        assert!(reader.is_virtual("veth1234"));
+        assert!(!reader.is_virtual("enp3s0"));
+        assert!(reader.is_up("enp3s0"));
+    }
+}
@@ -0,0 +1,459 @@
+use crate::models::{AlertState, Metric, Severity};
+use crate::services::alerts::AlertEvent;
+use crate::services::format_metric;
+use anyhow::{Result, anyhow};
+
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::sync::{Arc, Mutex, mpsc};
+#[cfg(target_os = "linux")]
+use zbus::blocking::Connection;
+#[cfg(target_os = "linux")]
+use zbus::zvariant::Value;
+
+/// The action key servers send back when the body of a notification is
+/// clicked.
+#[cfg(target_os = "linux")]
+const DEFAULT_ACTION: &str = "default";
+
+#[cfg(target_os = "linux")]
+#[zbus::proxy(
+    interface = "org.freedesktop.Notifications",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications",
+    gen_async = false,
+    blocking_name = "NotificationsProxy"
+)]
+trait Notifications {
+    #[allow(clippy::too_many_arguments)]
+    fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: &[&str],
+        hints: HashMap<&str, Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::Result<u32>;
+
+    #[zbus(signal)]
+    fn action_invoked(&self, id: u32, action_key: String) -> zbus::Result<()>;
+}
+
+/// Sends alert rules firing and resolving to the desktop's notification
+/// server over the session bus. Each rule keeps one notification, which
+/// its next event replaces. The D-Bus calls happen on a thread of their
+/// own so a slow server never holds up the caller. With click-through on,
+/// another thread listens for clicks and calls `on_click` with the metric
+/// the clicked notification was about.
+#[cfg(target_os = "linux")]
+pub struct Notifier {
+    events: mpsc::Sender<AlertEvent>,
+    /// Shared with the sender, so tests can tell when a notification's id
+    /// has been noted.
+    #[cfg(test)]
+    sent: Arc<Mutex<HashMap<u32, Metric>>>,
+}
+
+/// The sending side of `Notifier`, owning the bus proxy.
+#[cfg(target_os = "linux")]
+struct NotificationSender {
+    proxy: NotificationsProxy<'static>,
+    click_to_open: bool,
+    /// The metric each notification we sent is about, by notification id.
+    sent: Arc<Mutex<HashMap<u32, Metric>>>,
+    shown: HashMap<String, u32>,
+}
+
+#[cfg(target_os = "linux")]
+impl Notifier {
+    pub fn connect<F>(click_to_open: bool, on_click: F) -> Result<Self>
+    where
+        F: Fn(Metric) + Send + 'static,
+    {
+        Self::with_connection(Connection::session()?, click_to_open, on_click)
+    }
+
+    fn with_connection<F>(connection: Connection, click_to_open: bool, on_click: F) -> Result<Self>
+    where
+        F: Fn(Metric) + Send + 'static,
+    {
+        let proxy = NotificationsProxy::new(&connection)?;
+        let sent = Arc::new(Mutex::new(HashMap::new()));
+
+        if click_to_open {
+            let signals = proxy.receive_action_invoked()?;
+            let sent = Arc::clone(&sent);
+
+            std::thread::Builder::new()
+                .name("perch-notifications".to_string())
+                .spawn(move || {
+                    for signal in signals {
+                        let Ok(args) = signal.args() else {
+                            continue;
+                        };
+                        if args.action_key != DEFAULT_ACTION {
+                            continue;
+                        }
+
+                        let metric = sent
+                            .lock()
+                            .ok()
+                            .and_then(|sent| sent.get(&args.id).copied());
+                        if let Some(metric) = metric {
+                            on_click(metric);
+                        }
+                    }
+                })?;
+        }
+
+        let mut notifications = NotificationSender {
+            proxy,
+            click_to_open,
+            sent: Arc::clone(&sent),
+            shown: HashMap::new(),
+        };
+        let (events, received) = mpsc::channel::<AlertEvent>();
+        std::thread::Builder::new()
+            .name("perch-notify".to_string())
+            .spawn(move || {
+                for event in received {
+                    if let Err(e) = notifications.send(&event) {
+                        eprintln!("Failed to send notification: {}", e);
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            events,
+            #[cfg(test)]
+            sent,
+        })
+    }
+
+    /// Queues a notification for a rule that fired or resolved. Other
+    /// events are ignored.
+    pub fn notify(&self, event: &AlertEvent) -> Result<()> {
+        if urgency(event).is_none() {
+            return Ok(());
+        }
+        self.events
+            .send(event.clone())
+            .map_err(|_| anyhow!("The notification thread has stopped"))
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl NotificationSender {
+    fn send(&mut self, event: &AlertEvent) -> Result<()> {
+        let Some(urgency) = urgency(event) else {
+            return Ok(());
+        };
+
+        let rule = &event.rule;
+        let summary = format!("{} {}", rule.name, event.state);
+        let body = format!(
+            "{} (now {})",
+            rule.condition,
+            format_metric(rule.condition.metric, event.value)
+        );
+        let actions: &[&str] = if self.click_to_open {
+            &[DEFAULT_ACTION, "Open details"]
+        } else {
+            &[]
+        };
+        let hints = HashMap::from([("urgency", Value::U8(urgency))]);
+        let replaces = self.shown.get(&rule.name).copied().unwrap_or(0);
+
+        let id = self
+            .proxy
+            .notify("perch", replaces, "", &summary, &body, actions, hints, -1)?;
+
+        self.shown.insert(rule.name.clone(), id);
+        if let Ok(mut sent) = self.sent.lock() {
+            sent.remove(&replaces);
+            sent.insert(id, rule.condition.metric);
+        }
+        Ok(())
+    }
+}
+
+/// Desktop notifications need D-Bus, so elsewhere connecting always fails.
+#[cfg(not(target_os = "linux"))]
+pub struct Notifier;
+
+#[cfg(not(target_os = "linux"))]
+impl Notifier {
+    pub fn connect<F>(_click_to_open: bool, _on_click: F) -> Result<Self>
+    where
+        F: Fn(Metric) + Send + 'static,
+    {
+        Err(anyhow!("Desktop notifications are only supported on Linux"))
+    }
+
+    pub fn notify(&self, _event: &AlertEvent) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The notification urgency for an event: 0 (low), 1 (normal) or 2
+/// (critical). Resolving is always low; only firing and resolving are
+/// worth a notification.
+fn urgency(event: &AlertEvent) -> Option<u8> {
+    match event.state {
+        AlertState::Firing => Some(match event.rule.severity {
+            Severity::Info => 0,
+            Severity::Warning => 1,
+            Severity::Critical => 2,
+        }),
+        AlertState::Resolved => Some(0),
+        AlertState::Ok | AlertState::Pending => None,
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::models::AlertRule;
+    use std::io::{BufRead, BufReader};
+    use std::process::{Child, Command, Stdio};
+    use std::time::{Duration, Instant, SystemTime};
+    use tempfile::TempDir;
+    use zbus::zvariant::OwnedValue;
+
+    /// A `dbus-daemon` of our own, so the tests never touch the user's
+    /// session bus.
+    struct PrivateBus {
+        daemon: Child,
+        address: String,
+        _dir: TempDir,
+    }
+
+    impl PrivateBus {
+        /// Starts the daemon. It has to be installed for these tests to run.
+        fn start() -> Self {
+            let dir = TempDir::new().unwrap();
+            let config = dir.path().join("bus.conf");
+            std::fs::write(
+                &config,
+                format!(
+                    r#"<!DOCTYPE busconfig PUBLIC "-//freedesktop//DTD D-Bus Bus Configuration 1.0//EN"
+ "http://www.freedesktop.org/standards/dbus/1.0/busconfig.dtd">
+<busconfig>
+  <type>session</type>
+  <listen>unix:path={}</listen>
+  <auth>EXTERNAL</auth>
+  <policy context="default">
+    <allow send_destination="*" eavesdrop="true"/>
+    <allow eavesdrop="true"/>
+    <allow own="*"/>
+  </policy>
+</busconfig>"#,
+                    dir.path().join("bus").display()
+                ),
+            )
+            .unwrap();
+
+            let mut daemon = Command::new("dbus-daemon")
+                .arg(format!("--config-file={}", config.display()))
+                .args(["--nofork", "--print-address"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .expect("dbus-daemon is needed to test notifications");
+
+            let mut address = String::new();
+            BufReader::new(daemon.stdout.take().unwrap())
+                .read_line(&mut address)
+                .unwrap();
+
+            Self {
+                daemon,
+                address: address.trim().to_string(),
+                _dir: dir,
+            }
+        }
+
+        fn connect(&self) -> zbus::blocking::connection::Builder<'static> {
+            zbus::blocking::connection::Builder::address(self.address.as_str()).unwrap()
+        }
+    }
+
+    impl Drop for PrivateBus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+            let _ = self.daemon.wait();
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Received {
+        replaces_id: u32,
+        summary: String,
+        actions: Vec<String>,
+        urgency: u8,
+    }
+
+    /// Records what it's sent, standing in for a desktop's notification
+    /// server.
+    #[derive(Default)]
+    struct FakeServer {
+        received: Arc<Mutex<Vec<Received>>>,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.Notifications")]
+    impl FakeServer {
+        #[allow(clippy::too_many_arguments)]
+        fn notify(
+            &self,
+            _app_name: String,
+            replaces_id: u32,
+            _app_icon: String,
+            summary: String,
+            _body: String,
+            actions: Vec<String>,
+            hints: HashMap<String, OwnedValue>,
+            _expire_timeout: i32,
+        ) -> u32 {
+            let urgency = hints
+                .get("urgency")
+                .and_then(|value| u8::try_from(value).ok())
+                .unwrap_or(u8::MAX);
+
+            let mut received = self.received.lock().unwrap();
+            received.push(Received {
+                replaces_id,
+                summary,
+                actions,
+                urgency,
+            });
+            if replaces_id != 0 {
+                replaces_id
+            } else {
+                received.len() as u32
+            }
+        }
+    }
+
+    fn event(state: AlertState, severity: Severity) -> AlertEvent {
+        AlertEvent {
+            rule: AlertRule {
+                name: "swap".to_string(),
+                condition: "swap.used_pct > 50".parse().unwrap(),
+                hysteresis: 0.0,
+                severity,
+                command: None,
+                cooldown_secs: 0.0,
+            },
+            state,
+            value: 75.0,
+            timestamp: SystemTime::UNIX_EPOCH,
+            monotonic: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_notifies_and_reports_clicks() {
+        let bus = PrivateBus::start();
+
+        let server = FakeServer::default();
+        let received = Arc::clone(&server.received);
+        let server_connection = bus
+            .connect()
+            .name("org.freedesktop.Notifications")
+            .unwrap()
+            .serve_at("/org/freedesktop/Notifications", server)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let (clicked, clicks) = mpsc::channel();
+        let notifier =
+            Notifier::with_connection(bus.connect().build().unwrap(), true, move |metric| {
+                let _ = clicked.send(metric);
+            })
+            .unwrap();
+
+        notifier
+            .notify(&event(AlertState::Pending, Severity::Critical))
+            .unwrap();
+        notifier
+            .notify(&event(AlertState::Firing, Severity::Critical))
+            .unwrap();
+        notifier
+            .notify(&event(AlertState::Resolved, Severity::Critical))
+            .unwrap();
+
+        // Sending happens on the notifier's own thread, which notes the id
+        // the server handed back once the call returns.
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let noted = || {
+            received.lock().unwrap().len() >= 2 && notifier.sent.lock().unwrap().contains_key(&1)
+        };
+        while !noted() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+
+        let actions = vec![DEFAULT_ACTION.to_string(), "Open details".to_string()];
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![
+                Received {
+                    replaces_id: 0,
+                    summary: "swap firing".to_string(),
+                    actions: actions.clone(),
+                    urgency: 2,
+                },
+                Received {
+                    replaces_id: 1,
+                    summary: "swap resolved".to_string(),
+                    actions,
+                    urgency: 0,
+                },
+            ]
+        );
+
+        // Only a plain click on one of our own notifications counts.
+        for (id, action) in [(7u32, DEFAULT_ACTION), (1, "dismiss"), (1, DEFAULT_ACTION)] {
+            server_connection
+                .emit_signal(
+                    None::<&str>,
+                    "/org/freedesktop/Notifications",
+                    "org.freedesktop.Notifications",
+                    "ActionInvoked",
+                    &(id, action),
+                )
+                .unwrap();
+        }
+
+        assert_eq!(
+            clicks.recv_timeout(Duration::from_secs(5)),
+            Ok(Metric::SwapUsage)
+        );
+        assert!(clicks.recv_timeout(Duration::from_millis(200)).is_err());
+    }
+
+    #[test]
+    fn test_urgency_follows_severity() {
+        assert_eq!(urgency(&event(AlertState::Firing, Severity::Info)), Some(0));
+        assert_eq!(
+            urgency(&event(AlertState::Firing, Severity::Warning)),
+            Some(1)
+        );
+        assert_eq!(
+            urgency(&event(AlertState::Firing, Severity::Critical)),
+            Some(2)
+        );
+        assert_eq!(
+            urgency(&event(AlertState::Resolved, Severity::Critical)),
+            Some(0)
+        );
+        assert_eq!(
+            urgency(&event(AlertState::Pending, Severity::Critical)),
+            None
+        );
+    }
+}
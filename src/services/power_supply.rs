@@ -0,0 +1,232 @@
+use crate::models::{BatteryMetrics, BatteryState};
+use crate::services::sysfs::{read_parsed, read_trimmed};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub struct PowerSupplyReader {
+    sysfs_root: PathBuf,
+}
+
+impl PowerSupplyReader {
+    pub fn new() -> Self {
+        Self::with_root("/sys")
+    }
+
+    pub fn with_root(sysfs_root: impl Into<PathBuf>) -> Self {
+        Self {
+            sysfs_root: sysfs_root.into(),
+        }
+    }
+
+    /// Reads every system battery under `/sys/class/power_supply`. Batteries
+    /// with `scope=Device` belong to peripherals (mice, headsets) and are
+    /// skipped, as are AC adapters.
+    pub fn read(&self) -> Vec<BatteryMetrics> {
+        let mut supplies: Vec<PathBuf> =
+            match fs::read_dir(self.sysfs_root.join("class").join("power_supply")) {
+                Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).collect(),
+                Err(_) => return Vec::new(),
+            };
+        supplies.sort();
+
+        supplies
+            .iter()
+            .filter(|supply| read_trimmed(&supply.join("type")).as_deref() == Some("Battery"))
+            .filter(|supply| read_trimmed(&supply.join("scope")).as_deref() != Some("Device"))
+            .filter_map(|supply| read_battery(supply))
+            .collect()
+    }
+}
+
+impl Default for PowerSupplyReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn read_battery(supply: &Path) -> Option<BatteryMetrics> {
+    let read = |name: &str| read_parsed::<u64>(&supply.join(name));
+    // Some drivers sign power and current by direction, negative while
+    // discharging. Only the size matters here; `status` gives the direction.
+    let read_rate = |name: &str| read_parsed::<i64>(&supply.join(name)).map(i64::unsigned_abs);
+
+    let state = match read_trimmed(&supply.join("status")).as_deref() {
+        Some("Charging") => BatteryState::Charging,
+        Some("Discharging") => BatteryState::Discharging,
+        Some("Full") => BatteryState::Full,
+        Some("Not charging") => BatteryState::NotCharging,
+        _ => BatteryState::Unknown,
+    };
+
+    // Drivers report either energy (µWh) and power (µW), or charge (µAh) and
+    // current (µA). Both pairs give the same ratios, so we can work in
+    // whichever one is present.
+    let voltage = read("voltage_now");
+    let levels = match (read("energy_now"), read("energy_full")) {
+        (Some(now), Some(full)) => Some((now, full, read_rate("power_now"))),
+        _ => match (read("charge_now"), read("charge_full")) {
+            (Some(now), Some(full)) => Some((now, full, read_rate("current_now"))),
+            _ => None,
+        },
+    };
+
+    let charge_percentage = match (read("capacity"), levels) {
+        (Some(capacity), _) => capacity as f32,
+        (None, Some((now, full, _))) if full > 0 => (now as f32 / full as f32 * 100.0).min(100.0),
+        _ => return None,
+    };
+
+    let power_watts = match (read_rate("power_now"), read_rate("current_now"), voltage) {
+        (Some(power), _, _) => Some(power as f32 / 1_000_000.0),
+        (None, Some(current), Some(voltage)) => {
+            Some((current as f64 * voltage as f64 / 1e12) as f32)
+        }
+        _ => None,
+    };
+
+    let time_until = |remaining: u64, rate: Option<u64>| -> Option<Duration> {
+        let rate = rate.filter(|&rate| rate > 0)?;
+        Some(Duration::from_secs_f64(
+            remaining as f64 / rate as f64 * 3600.0,
+        ))
+    };
+
+    let (time_to_full, time_to_empty) = match (state, levels) {
+        (BatteryState::Charging, Some((now, full, rate))) => {
+            (time_until(full.saturating_sub(now), rate), None)
+        }
+        (BatteryState::Discharging, Some((now, _, rate))) => (None, time_until(now, rate)),
+        _ => (None, None),
+    };
+
+    Some(BatteryMetrics {
+        name: supply.file_name()?.to_string_lossy().into_owned(),
+        charge_percentage,
+        state,
+        power_watts,
+        time_to_full,
+        time_to_empty,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::sysfs::write_fixture;
+    use tempfile::tempdir;
+
+    fn supply(root: &Path, name: &str, files: &[(&str, &str)]) {
+        let dir = root.join("class/power_supply").join(name);
+        for (file, contents) in files {
+            write_fixture(&dir.join(file), contents);
+        }
+    }
+
+    #[test]
+    fn test_reads_discharging_energy_battery() {
+        let dir = tempdir().unwrap();
+        supply(dir.path(), "AC", &[("type", "Mains"), ("online", "0")]);
+        supply(
+            dir.path(),
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("status", "Discharging"),
+                ("capacity", "50"),
+                ("energy_now", "30000000"),
+                ("energy_full", "60000000"),
+                ("power_now", "15000000"),
+            ],
+        );
+
+        let batteries = PowerSupplyReader::with_root(dir.path()).read();
+
+        assert_eq!(batteries.len(), 1);
+        let battery = &batteries[0];
+        assert_eq!(battery.name, "BAT0");
+        assert_eq!(battery.state, BatteryState::Discharging);
+        assert_eq!(battery.charge_percentage, 50.0);
+        assert_eq!(battery.power_watts, Some(15.0));
+        assert_eq!(battery.time_to_empty, Some(Duration::from_secs(2 * 3600)));
+        assert_eq!(battery.time_to_full, None);
+    }
+
+    #[test]
+    fn test_reads_charging_charge_battery() {
+        let dir = tempdir().unwrap();
+        supply(
+            dir.path(),
+            "BAT1",
+            &[
+                ("type", "Battery"),
+                ("status", "Charging"),
+                ("charge_now", "1000000"),
+                ("charge_full", "4000000"),
+                ("current_now", "2000000"),
+                ("voltage_now", "12000000"),
+            ],
+        );
+
+        let batteries = PowerSupplyReader::with_root(dir.path()).read();
+        let battery = &batteries[0];
+
+        assert_eq!(battery.state, BatteryState::Charging);
+        assert_eq!(battery.charge_percentage, 25.0);
+        assert_eq!(battery.power_watts, Some(24.0));
+        assert_eq!(battery.time_to_full, Some(Duration::from_secs(90 * 60)));
+    }
+
+    #[test]
+    fn test_negative_current_reads_as_discharge_rate() {
+        let dir = tempdir().unwrap();
+        supply(
+            dir.path(),
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("status", "Discharging"),
+                ("charge_now", "3000000"),
+                ("charge_full", "4000000"),
+                ("current_now", "-1500000"),
+                ("voltage_now", "12000000"),
+            ],
+        );
+
+        let batteries = PowerSupplyReader::with_root(dir.path()).read();
+        let battery = &batteries[0];
+
+        assert_eq!(battery.power_watts, Some(18.0));
+        assert_eq!(battery.time_to_empty, Some(Duration::from_secs(2 * 3600)));
+    }
+
+    #[test]
+    fn test_skips_peripheral_batteries_and_missing_class() {
+        let dir = tempdir().unwrap();
+        assert!(PowerSupplyReader::with_root(dir.path()).read().is_empty());
+
+        supply(
+            dir.path(),
+            "hidpp_battery_0",
+            &[("type", "Battery"), ("scope", "Device"), ("capacity", "80")],
+        );
+        assert!(PowerSupplyReader::with_root(dir.path()).read().is_empty());
+    }
+
+    #[test]
+    fn test_capacity_only_battery() {
+        let dir = tempdir().unwrap();
+        supply(
+            dir.path(),
+            "BAT0",
+            &[("type", "Battery"), ("status", "Full"), ("capacity", "100")],
+        );
+
+        let batteries = PowerSupplyReader::with_root(dir.path()).read();
+
+        assert_eq!(batteries[0].state, BatteryState::Full);
+        assert_eq!(batteries[0].charge_percentage, 100.0);
+        assert_eq!(batteries[0].power_watts, None);
+        assert_eq!(batteries[0].time_to_empty, None);
+    }
+}
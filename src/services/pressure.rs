@@ -0,0 +1,128 @@
+use crate::models::{PressureMetrics, PressureStats, ResourcePressure};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Reads Pressure Stall Information from `/proc/pressure`. Kernels built
+/// without PSI, or booted with `psi=0`, leave every resource as `None`.
+pub struct PressureReader {
+    procfs_root: PathBuf,
+}
+
+impl PressureReader {
+    pub fn new() -> Self {
+        Self::with_root("/proc")
+    }
+
+    pub fn with_root(procfs_root: impl Into<PathBuf>) -> Self {
+        Self {
+            procfs_root: procfs_root.into(),
+        }
+    }
+
+    pub fn read(&self) -> PressureMetrics {
+        PressureMetrics {
+            cpu: self.read_resource("cpu"),
+            memory: self.read_resource("memory"),
+            io: self.read_resource("io"),
+        }
+    }
+
+    fn read_resource(&self, resource: &str) -> Option<ResourcePressure> {
+        let contents = fs::read_to_string(self.procfs_root.join("pressure").join(resource)).ok()?;
+        parse_pressure(&contents)
+    }
+}
+
+impl Default for PressureReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parses lines of the form
+/// `some avg10=0.12 avg60=0.05 avg300=0.01 total=123456`, where `total` is
+/// the cumulative stall time in microseconds.
+pub fn parse_pressure(contents: &str) -> Option<ResourcePressure> {
+    let mut some = None;
+    let mut full = None;
+
+    for line in contents.lines() {
+        let mut fields = line.split_whitespace();
+        let kind = fields.next();
+
+        let mut stats = PressureStats::default();
+        for field in fields {
+            let Some((key, value)) = field.split_once('=') else {
+                continue;
+            };
+            match key {
+                "avg10" => stats.avg10 = value.parse().ok()?,
+                "avg60" => stats.avg60 = value.parse().ok()?,
+                "avg300" => stats.avg300 = value.parse().ok()?,
+                "total" => stats.total = Duration::from_micros(value.parse().ok()?),
+                _ => {}
+            }
+        }
+
+        match kind {
+            Some("some") => some = Some(stats),
+            Some("full") => full = Some(stats),
+            _ => {}
+        }
+    }
+
+    Some(ResourcePressure { some: some?, full })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const MEMORY: &str = "\
+some avg10=1.50 avg60=0.75 avg300=0.20 total=2500000
+full avg10=0.40 avg60=0.10 avg300=0.00 total=600000
+";
+
+    #[test]
+    fn test_parse_pressure_some_and_full() {
+        let pressure = parse_pressure(MEMORY).unwrap();
+
+        assert_eq!(pressure.some.avg10, 1.5);
+        assert_eq!(pressure.some.avg60, 0.75);
+        assert_eq!(pressure.some.avg300, 0.2);
+        assert_eq!(pressure.some.total, Duration::from_millis(2500));
+        assert_eq!(pressure.full.unwrap().avg10, 0.4);
+    }
+
+    #[test]
+    fn test_parse_pressure_without_full_line() {
+        let pressure = parse_pressure("some avg10=3.00 avg60=2.00 avg300=1.00 total=10\n").unwrap();
+
+        assert_eq!(pressure.some.avg10, 3.0);
+        assert!(pressure.full.is_none());
+        assert!(parse_pressure("").is_none());
+        assert!(parse_pressure("some avg10=abc avg60=0 avg300=0 total=0\n").is_none());
+    }
+
+    #[test]
+    fn test_reader_uses_procfs_root() {
+        let dir = tempdir().unwrap();
+        assert!(!PressureReader::with_root(dir.path()).read().is_available());
+
+        fs::create_dir_all(dir.path().join("pressure")).unwrap();
+        fs::write(dir.path().join("pressure/memory"), MEMORY).unwrap();
+        fs::write(
+            dir.path().join("pressure/io"),
+            "some avg10=12.00 avg60=8.00 avg300=4.00 total=99\n",
+        )
+        .unwrap();
+
+        let pressure = PressureReader::with_root(dir.path()).read();
+
+        assert!(pressure.cpu.is_none());
+        assert_eq!(pressure.memory.unwrap().some.avg60, 0.75);
+        assert_eq!(pressure.worst_avg10(), 12.0);
+    }
+}
@@ -0,0 +1,148 @@
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProcessCounts {
+    pub processes: u32,
+    pub threads: u32,
+    pub running: u32,
+    pub zombies: u32,
+}
+
+/// Counts processes by state from `/proc/<pid>/stat`. This only reads one
+/// small file per process, which is far cheaper than a full process refresh
+/// but still grows with the number of processes; `read_loadavg` gets the
+/// running and thread counts from a single file.
+pub struct ProcessCountReader {
+    procfs_root: PathBuf,
+}
+
+impl ProcessCountReader {
+    pub fn new() -> Self {
+        Self::with_root("/proc")
+    }
+
+    pub fn with_root(procfs_root: impl Into<PathBuf>) -> Self {
+        Self {
+            procfs_root: procfs_root.into(),
+        }
+    }
+
+    /// The runnable and total thread counts from `/proc/loadavg`, whose
+    /// fourth field is `running/total`.
+    pub fn read_loadavg(&self) -> Option<(u32, u32)> {
+        let contents = fs::read_to_string(self.procfs_root.join("loadavg")).ok()?;
+        let (running, total) = contents.split_whitespace().nth(3)?.split_once('/')?;
+        Some((running.parse().ok()?, total.parse().ok()?))
+    }
+
+    /// Walks every process for the full set of counts.
+    pub fn read(&self) -> ProcessCounts {
+        let mut counts = ProcessCounts::default();
+
+        let entries = match fs::read_dir(&self.procfs_root) {
+            Ok(entries) => entries,
+            Err(_) => return counts,
+        };
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            let is_pid = entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.bytes().all(|b| b.is_ascii_digit()));
+            if !is_pid {
+                continue;
+            }
+
+            // Processes can exit between listing and reading.
+            let Ok(stat) = fs::read_to_string(entry.path().join("stat")) else {
+                continue;
+            };
+            let Some((state, threads)) = parse_stat(&stat) else {
+                continue;
+            };
+
+            counts.processes += 1;
+            counts.threads += threads;
+            match state {
+                'R' => counts.running += 1,
+                'Z' => counts.zombies += 1,
+                _ => {}
+            }
+        }
+
+        counts
+    }
+}
+
+impl Default for ProcessCountReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Extracts the state and thread count from a `/proc/<pid>/stat` line. The
+/// command name is wrapped in parentheses and may itself contain spaces or
+/// parentheses, so fields are counted from the last `)`.
+fn parse_stat(stat: &str) -> Option<(char, u32)> {
+    let rest = &stat[stat.rfind(')')? + 1..];
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+
+    let state = fields.first()?.chars().next()?;
+    let threads = fields.get(17)?.parse().ok()?;
+
+    Some((state, threads))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::sysfs::write_fixture;
+    use tempfile::tempdir;
+
+    fn stat_line(pid: u32, comm: &str, state: char, threads: u32) -> String {
+        format!(
+            "{} ({}) {} 1 1 1 0 -1 4194304 78 0 0 0 0 0 0 0 20 0 {} 0 179774 2703360 286",
+            pid, comm, state, threads
+        )
+    }
+
+    #[test]
+    fn test_parse_stat_handles_odd_command_names() {
+        let stat = stat_line(42, "weird) name (x", 'S', 7);
+        assert_eq!(parse_stat(&stat), Some(('S', 7)));
+        assert_eq!(parse_stat("garbage"), None);
+    }
+
+    #[test]
+    fn test_reads_loadavg_counts() {
+        let dir = tempdir().unwrap();
+        let reader = ProcessCountReader::with_root(dir.path());
+        assert_eq!(reader.read_loadavg(), None);
+
+        write_fixture(&dir.path().join("loadavg"), "0.52 0.58 0.59 3/1024 98765");
+        assert_eq!(reader.read_loadavg(), Some((3, 1024)));
+    }
+
+    #[test]
+    fn test_reader_counts_states_from_fixture() {
+        let dir = tempdir().unwrap();
+        for (pid, state, threads) in [(1, 'S', 1), (20, 'R', 4), (31, 'Z', 1), (400, 'R', 2)] {
+            let stat = dir.path().join(pid.to_string()).join("stat");
+            write_fixture(&stat, &stat_line(pid, "proc", state, threads));
+        }
+        fs::create_dir_all(dir.path().join("sys")).unwrap();
+
+        let counts = ProcessCountReader::with_root(dir.path()).read();
+
+        assert_eq!(
+            counts,
+            ProcessCounts {
+                processes: 4,
+                threads: 8,
+                running: 2,
+                zombies: 1,
+            }
+        );
+    }
+}
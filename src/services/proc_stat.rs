@@ -0,0 +1,194 @@
+use crate::models::CpuTimeBreakdown;
+use std::fs;
+use std::path::PathBuf;
+
+/// Cumulative jiffies for one `cpu` line of `/proc/stat`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CpuTimes {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+    pub guest: u64,
+    pub guest_nice: u64,
+}
+
+impl CpuTimes {
+    /// The kernel already counts guest time inside user and nice, so it is
+    /// left out here to avoid counting it twice.
+    fn total(&self) -> u64 {
+        self.user
+            + self.nice
+            + self.system
+            + self.idle
+            + self.iowait
+            + self.irq
+            + self.softirq
+            + self.steal
+    }
+
+    /// Share of each state between two readings, as percentages. Counters
+    /// that went backwards (CPU hotplug) are treated as zero.
+    pub fn breakdown_since(&self, previous: &CpuTimes) -> CpuTimeBreakdown {
+        let total = self.total().saturating_sub(previous.total());
+        if total == 0 {
+            return CpuTimeBreakdown::default();
+        }
+
+        let pct = |now: u64, before: u64| now.saturating_sub(before) as f32 / total as f32 * 100.0;
+        let guest = pct(self.guest, previous.guest);
+        let guest_nice = pct(self.guest_nice, previous.guest_nice);
+
+        CpuTimeBreakdown {
+            user: (pct(self.user, previous.user) - guest).max(0.0),
+            nice: (pct(self.nice, previous.nice) - guest_nice).max(0.0),
+            system: pct(self.system, previous.system),
+            idle: pct(self.idle, previous.idle),
+            iowait: pct(self.iowait, previous.iowait),
+            irq: pct(self.irq, previous.irq),
+            softirq: pct(self.softirq, previous.softirq),
+            steal: pct(self.steal, previous.steal),
+            guest: guest + guest_nice,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcStat {
+    pub total: CpuTimes,
+    pub per_cpu: Vec<(usize, CpuTimes)>,
+}
+
+pub struct ProcStatReader {
+    procfs_root: PathBuf,
+}
+
+impl ProcStatReader {
+    pub fn new() -> Self {
+        Self::with_root("/proc")
+    }
+
+    pub fn with_root(procfs_root: impl Into<PathBuf>) -> Self {
+        Self {
+            procfs_root: procfs_root.into(),
+        }
+    }
+
+    pub fn read(&self) -> Option<ProcStat> {
+        let contents = fs::read_to_string(self.procfs_root.join("stat")).ok()?;
+        parse_proc_stat(&contents)
+    }
+}
+
+impl Default for ProcStatReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn parse_proc_stat(contents: &str) -> Option<ProcStat> {
+    let mut stat = ProcStat::default();
+    let mut found_total = false;
+
+    for line in contents.lines() {
+        let mut fields = line.split_whitespace();
+        let Some(name) = fields.next() else {
+            continue;
+        };
+        let Some(cpu) = name.strip_prefix("cpu") else {
+            continue;
+        };
+
+        // Older kernels omit the trailing columns, which then read as zero.
+        let values: Vec<u64> = fields.map(|f| f.parse().unwrap_or(0)).collect();
+        let value = |i: usize| values.get(i).copied().unwrap_or(0);
+        let times = CpuTimes {
+            user: value(0),
+            nice: value(1),
+            system: value(2),
+            idle: value(3),
+            iowait: value(4),
+            irq: value(5),
+            softirq: value(6),
+            steal: value(7),
+            guest: value(8),
+            guest_nice: value(9),
+        };
+
+        if cpu.is_empty() {
+            stat.total = times;
+            found_total = true;
+        } else if let Ok(index) = cpu.parse() {
+            stat.per_cpu.push((index, times));
+        }
+    }
+
+    found_total.then_some(stat)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+    use tempfile::tempdir;
+
+    const STAT: &str = "\
+cpu  100 10 50 800 20 5 5 10 0 0
+cpu0 60 10 20 390 10 5 5 0 0 0
+cpu1 40 0 30 410 10 0 0 10 0 0
+intr 12345 0 0
+ctxt 99999
+";
+
+    #[test]
+    fn test_parse_proc_stat_reads_total_and_cores() {
+        let stat = parse_proc_stat(STAT).unwrap();
+
+        assert_eq!(stat.total.user, 100);
+        assert_eq!(stat.total.steal, 10);
+        assert_eq!(stat.per_cpu.len(), 2);
+        assert_eq!(stat.per_cpu[1].0, 1);
+        assert_eq!(stat.per_cpu[1].1.system, 30);
+        assert!(parse_proc_stat("intr 0\n").is_none());
+    }
+
+    #[test]
+    fn test_breakdown_since_previous_sample() {
+        let before = CpuTimes::default();
+        let after = CpuTimes {
+            user: 30,
+            nice: 0,
+            system: 10,
+            idle: 40,
+            iowait: 10,
+            irq: 0,
+            softirq: 0,
+            steal: 10,
+            guest: 10,
+            guest_nice: 0,
+        };
+
+        let breakdown = after.breakdown_since(&before);
+
+        assert_approx_eq!(breakdown.user, 20.0, 1e-3);
+        assert_approx_eq!(breakdown.guest, 10.0, 1e-3);
+        assert_approx_eq!(breakdown.system, 10.0, 1e-3);
+        assert_approx_eq!(breakdown.iowait, 10.0, 1e-3);
+        assert_approx_eq!(breakdown.steal, 10.0, 1e-3);
+        assert_approx_eq!(breakdown.idle, 40.0, 1e-3);
+        assert_eq!(after.breakdown_since(&after), CpuTimeBreakdown::default());
+    }
+
+    #[test]
+    fn test_reader_uses_procfs_root() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("stat"), STAT).unwrap();
+
+        let stat = ProcStatReader::with_root(dir.path()).read().unwrap();
+        assert_eq!(stat.total.idle, 800);
+    }
+}
@@ -0,0 +1,183 @@
+use crate::models::{SystemMetrics, UserConfig};
+use crate::services::SystemMonitor;
+use anyhow::Result;
+use std::time::Duration;
+use tokio::runtime::{Builder, Runtime};
+use tokio::sync::watch;
+
+/// Shortest interval the sampler will tick at.
+const MIN_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Runs `SystemMonitor::collect` on a background runtime so that slow
+/// sampling never holds up a frame. `on_collect` sees every snapshot on the
+/// sampling thread, so anything that must not miss one (alerts) doesn't
+/// depend on the UI drawing. Each snapshot is then published over a watch
+/// channel and `on_sample` is called so the UI can schedule a repaint.
+pub struct Sampler {
+    _runtime: Runtime,
+    receiver: watch::Receiver<SystemMetrics>,
+    config: watch::Sender<Option<UserConfig>>,
+}
+
+impl Sampler {
+    pub fn spawn<C, F>(
+        monitor: SystemMonitor,
+        interval: Duration,
+        on_collect: C,
+        on_sample: F,
+    ) -> Result<Self>
+    where
+        C: FnMut(&SystemMetrics) + Send + 'static,
+        F: Fn() + Send + 'static,
+    {
+        let runtime = Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("perch-sampler")
+            .enable_time()
+            .build()?;
+
+        // `tokio::time::interval` panics on a zero period.
+        let interval = interval.max(MIN_INTERVAL);
+
+        let (sender, receiver) = watch::channel(SystemMetrics::zero());
+        let (config, config_updates) = watch::channel(None);
+        let task = runtime.spawn(run(
+            monitor,
+            interval,
+            sender,
+            config_updates,
+            on_collect,
+            on_sample,
+        ));
+        runtime.spawn(async move {
+            if let Err(e) = task.await {
+                eprintln!("Metric sampler stopped: {}", e);
+            }
+        });
+
+        Ok(Self {
+            _runtime: runtime,
+            receiver,
+            config,
+        })
+    }
+
+    /// Passes a changed config on to the monitor before its next sample.
+    pub fn apply_config(&self, config: &UserConfig) {
+        self.config.send_replace(Some(config.clone()));
+    }
+
+    /// Returns the newest snapshot if one arrived since the last call.
+    pub fn latest(&mut self) -> Option<SystemMetrics> {
+        if self.receiver.has_changed().unwrap_or(false) {
+            Some(self.receiver.borrow_and_update().clone())
+        } else {
+            None
+        }
+    }
+}
+
+async fn run<C, F>(
+    mut monitor: SystemMonitor,
+    interval: Duration,
+    sender: watch::Sender<SystemMetrics>,
+    mut config_updates: watch::Receiver<Option<UserConfig>>,
+    mut on_collect: C,
+    on_sample: F,
+) where
+    C: FnMut(&SystemMetrics) + Send + 'static,
+    F: Fn() + Send + 'static,
+{
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+
+        let config = if config_updates.has_changed().unwrap_or(false) {
+            config_updates.borrow_and_update().clone()
+        } else {
+            None
+        };
+        let collected = tokio::task::spawn_blocking(move || {
+            if let Some(config) = config {
+                monitor.apply_config(&config);
+            }
+            let metrics = monitor.collect();
+            on_collect(&metrics);
+            (monitor, on_collect, metrics)
+        })
+        .await;
+
+        let metrics = match collected {
+            Ok((returned, callback, metrics)) => {
+                monitor = returned;
+                on_collect = callback;
+                metrics
+            }
+            Err(e) => {
+                eprintln!("Metric sampling failed: {}", e);
+                return;
+            }
+        };
+
+        if sender.send(metrics).is_err() {
+            return;
+        }
+        on_sample();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_sampler_publishes_snapshots() {
+        let samples = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&samples);
+
+        let collected = Arc::new(AtomicUsize::new(0));
+        let seen = Arc::clone(&collected);
+
+        let mut sampler = Sampler::spawn(
+            SystemMonitor::new(),
+            Duration::from_millis(50),
+            move |_| {
+                seen.fetch_add(1, Ordering::SeqCst);
+            },
+            move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            },
+        )
+        .unwrap();
+
+        std::thread::sleep(Duration::from_millis(500));
+
+        assert!(samples.load(Ordering::SeqCst) > 0);
+        assert!(collected.load(Ordering::SeqCst) >= samples.load(Ordering::SeqCst));
+        let metrics = sampler.latest().expect("no snapshot was published");
+        assert!(metrics.memory.total_bytes > 0);
+    }
+
+    #[test]
+    fn test_zero_interval_still_samples() {
+        let samples = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&samples);
+
+        let _sampler = Sampler::spawn(
+            SystemMonitor::new(),
+            Duration::ZERO,
+            |_| {},
+            move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            },
+        )
+        .unwrap();
+
+        std::thread::sleep(Duration::from_millis(500));
+        assert!(samples.load(Ordering::SeqCst) > 0);
+    }
+}
@@ -0,0 +1,72 @@
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Whether a sysfs class entry links into `/sys/devices/virtual`, which is
+/// where the kernel places software-only devices (loopback, bridges, dm, ...).
+pub fn links_to_virtual(path: &Path) -> bool {
+    fs::read_link(path)
+        .map(|target| target.components().any(|c| c.as_os_str() == "virtual"))
+        .unwrap_or(false)
+}
+
+pub fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|contents| contents.trim().to_string())
+}
+
+pub fn read_parsed<T: FromStr>(path: &Path) -> Option<T> {
+    read_trimmed(path).and_then(|contents| contents.parse().ok())
+}
+
+/// Writes a one-line sysfs or procfs file for a test, creating the
+/// directories above it.
+#[cfg(test)]
+pub(crate) fn write_fixture(path: &Path, contents: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, format!("{}\n", contents)).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_links_to_virtual() {
+        let dir = tempdir().unwrap();
+        symlink("../../devices/virtual/net/lo", dir.path().join("lo")).unwrap();
+        symlink("../../devices/pci0000:00/net/eth0", dir.path().join("eth0")).unwrap();
+
+        assert!(links_to_virtual(&dir.path().join("lo")));
+        assert!(!links_to_virtual(&dir.path().join("eth0")));
+        assert!(!links_to_virtual(&dir.path().join("missing")));
+    }
+
+    #[test]
+    fn test_read_trimmed() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("operstate"), "up\n").unwrap();
+
+        assert_eq!(
+            read_trimmed(&dir.path().join("operstate")),
+            Some("up".to_string())
+        );
+        assert_eq!(read_trimmed(&dir.path().join("missing")), None);
+    }
+
+    #[test]
+    fn test_read_parsed() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("temp1_input"), "45000\n").unwrap();
+        fs::write(dir.path().join("garbage"), "n/a\n").unwrap();
+
+        assert_eq!(
+            read_parsed::<i64>(&dir.path().join("temp1_input")),
+            Some(45000)
+        );
+        assert_eq!(read_parsed::<i64>(&dir.path().join("garbage")), None);
+    }
+}
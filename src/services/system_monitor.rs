@@ -1,163 +1,419 @@
-use crate::models::{CPUMetrics, DiskMetrics, MemoryMetrics, NetworkMetrics, SystemMetrics};
-use std::time::SystemTime;
-use sysinfo::{Networks, System};
+use crate::models::{SystemMetrics, UserConfig};
+use crate::services::collectors::{
+    BatteryCollector, CgroupCollector, CollectContext, CpuCollector, DiskCollector,
+    FilesystemCollector, LoadCollector, MemoryCollector, MetricCollector, NetworkCollector,
+    PressureCollector, ProcessCollector, SensorsCollector, UnitCollector,
+};
+use crate::services::cpu_time::thread_cpu_time;
+use crate::services::{
+    CgroupReader, Clock, DiskStatsReader, MountFilter, MountReader, SystemClock,
+};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+/// How far the wall clock may run ahead of the monotonic clock between two
+/// samples before the gap is put down to a suspend rather than a slow tick.
+const SUSPEND_TOLERANCE: Duration = Duration::from_secs(5);
+
+/// A collector together with the monotonic time it last ran, so each one
+/// measures rates over its own interval.
+struct Registered {
+    collector: Box<dyn MetricCollector>,
+    last_collected: Duration,
+}
 
 pub struct SystemMonitor {
-    system: System,
-    networks: Networks,
-    previous_network: (u64, u64),
-    previous_disk: (u64, u64),
-    last_update: SystemTime,
+    collectors: Vec<Registered>,
+    /// The settings each registered optional collector was built with, so
+    /// that changing them rebuilds it.
+    optional_settings: HashMap<&'static str, String>,
+    clock: Box<dyn Clock>,
+    last_sample: (Duration, SystemTime),
 }
 
 impl SystemMonitor {
     pub fn new() -> Self {
-        let mut system = System::new_all();
-        system.refresh_all();
+        Self::from_config(&UserConfig::default())
+    }
 
-        let networks = Networks::new_with_refreshed_list();
+    /// Builds the standard set of collectors, leaving out any listed in
+    /// `UserConfig::disabled_collectors`. Collectors run in the order they
+    /// are registered.
+    pub fn from_config(config: &UserConfig) -> Self {
+        let mut collectors: Vec<Box<dyn MetricCollector>> = vec![
+            Box::new(CpuCollector::default()),
+            Box::new(MemoryCollector::default()),
+            // Reads the host totals the two above just filled in.
+            Box::new(CgroupCollector::default()),
+            Box::new(NetworkCollector::new(
+                config.include_virtual_interfaces,
+                config.rate_smoothing.network,
+            )),
+            Box::new(DiskCollector::new(
+                DiskStatsReader::new(),
+                config.rate_smoothing.disk,
+            )),
+            Box::new(FilesystemCollector::new(
+                MountReader::new(),
+                MountFilter {
+                    include: config.filesystem_include.clone(),
+                    exclude: config.filesystem_exclude.clone(),
+                },
+            )),
+            Box::new(LoadCollector::default()),
+            Box::new(PressureCollector::default()),
+            Box::new(SensorsCollector::default()),
+            Box::new(BatteryCollector::default()),
+        ];
+        collectors.retain(|collector| !is_disabled(config, collector.name()));
+
+        let mut monitor = Self::with_collectors(collectors);
+        monitor.apply_config(config);
+        monitor
+    }
 
-        Self {
-            system,
-            networks,
-            previous_network: (0, 0),
-            previous_disk: (0, 0),
-            last_update: SystemTime::now(),
+    /// Registers the collectors behind sections that can be shown or hidden
+    /// while running, processes and units, for as long as their section is
+    /// shown. Both are expensive enough that they shouldn't run otherwise.
+    /// One whose settings have changed is rebuilt with the new ones.
+    pub fn apply_config(&mut self, config: &UserConfig) {
+        let optional = [
+            (
+                "processes",
+                config.show_processes,
+                config.process_count.to_string(),
+            ),
+            ("units", config.show_units, config.unit_cgroup.clone()),
+        ];
+
+        for (name, shown, settings) in optional {
+            let wanted = shown && !is_disabled(config, name);
+            let current = self.optional_settings.get(name);
+
+            if current.is_some_and(|current| !wanted || *current != settings) {
+                self.collectors.retain(|c| c.collector.name() != name);
+                self.optional_settings.remove(name);
+            }
+            if wanted && !self.optional_settings.contains_key(name) {
+                let collector: Box<dyn MetricCollector> = match name {
+                    "processes" => Box::new(ProcessCollector::new(config.process_count)),
+                    _ => Box::new(UnitCollector::new(
+                        CgroupReader::new(),
+                        config.unit_cgroup.clone(),
+                    )),
+                };
+                self.register(collector);
+                self.optional_settings.insert(name, settings);
+            }
         }
     }
 
-    pub fn collect(&mut self) -> SystemMetrics {
-        self.system.refresh_all();
-        self.networks.refresh(true);
-        let now = SystemTime::now();
-
-        let cpu = self.collect_cpu_metrics();
-        let memory = self.collect_memory_metrics();
-        let network = self.collect_network_metrics(now);
-        let disk = self.collect_disk_metrics(now);
-
-        self.last_update = now;
-
-        SystemMetrics {
-            cpu,
-            memory,
-            network,
-            disk,
-            timestamp: now,
+    pub fn with_collectors(collectors: Vec<Box<dyn MetricCollector>>) -> Self {
+        Self::with_clock(collectors, Box::new(SystemClock::new()))
+    }
+
+    pub fn with_clock(collectors: Vec<Box<dyn MetricCollector>>, clock: Box<dyn Clock>) -> Self {
+        let mut monitor = Self {
+            collectors: Vec::new(),
+            optional_settings: HashMap::new(),
+            last_sample: (clock.monotonic(), clock.wall()),
+            clock,
+        };
+        for collector in collectors {
+            monitor.register(collector);
         }
+
+        monitor
     }
 
-    fn collect_cpu_metrics(&self) -> CPUMetrics {
-        let usage_percentage = self.system.global_cpu_usage();
+    pub fn register(&mut self, collector: Box<dyn MetricCollector>) {
+        self.collectors.push(Registered {
+            collector,
+            last_collected: self.clock.monotonic(),
+        });
+    }
 
-        let per_core: Vec<f32> = self
-            .system
-            .cpus()
-            .iter()
-            .map(|cpu| cpu.cpu_usage())
-            .collect();
+    #[cfg(test)]
+    pub fn collector_names(&self) -> Vec<&'static str> {
+        self.collectors.iter().map(|c| c.collector.name()).collect()
+    }
 
-        CPUMetrics {
-            usage_percentage,
-            per_core,
+    pub fn collect(&mut self) -> SystemMetrics {
+        let cpu_time_before = thread_cpu_time();
+        let monotonic = self.clock.monotonic();
+        let wall = self.clock.wall();
+
+        let mut metrics = SystemMetrics::zero();
+        metrics.timestamp = wall;
+        metrics.monotonic = monotonic;
+        metrics.gap_after_suspend = self.suspended_since_last_sample(monotonic, wall);
+
+        for registered in &mut self.collectors {
+            let now = self.clock.monotonic();
+            let ctx = CollectContext {
+                elapsed_secs: now.saturating_sub(registered.last_collected).as_secs_f64(),
+            };
+            registered.collector.collect(&ctx, &mut metrics);
+            registered.last_collected = now;
         }
-    }
 
-    fn collect_memory_metrics(&self) -> MemoryMetrics {
-        MemoryMetrics {
-            total_bytes: self.system.total_memory(),
-            used_bytes: self.system.used_memory(),
-            available_bytes: self.system.available_memory(),
-            swap_total_bytes: self.system.total_swap(),
-            swap_used_bytes: self.system.used_swap(),
+        self.last_sample = (monotonic, wall);
+
+        if let (Some(before), Some(after)) = (cpu_time_before, thread_cpu_time()) {
+            metrics.sample_cpu_time = after.saturating_sub(before);
         }
+
+        metrics
     }
 
-    fn collect_network_metrics(&mut self, now: SystemTime) -> NetworkMetrics {
-        let mut total_received = 0u64;
-        let mut total_transmitted = 0u64;
+    /// The monotonic clock stops during suspend while the wall clock keeps
+    /// going, so a wall clock that ran well ahead means the machine slept.
+    /// A wall clock that went backwards was just adjusted.
+    fn suspended_since_last_sample(&self, monotonic: Duration, wall: SystemTime) -> bool {
+        let (last_monotonic, last_wall) = self.last_sample;
+        let monotonic_elapsed = monotonic.saturating_sub(last_monotonic);
 
-        // Sum up all network interfaces
-        for (_interface_name, data) in &self.networks {
-            total_received += data.total_received();
-            total_transmitted += data.total_transmitted();
+        match wall.duration_since(last_wall) {
+            Ok(wall_elapsed) => wall_elapsed > monotonic_elapsed + SUSPEND_TOLERANCE,
+            Err(_) => false,
         }
+    }
+}
 
-        let time_delta = now
-            .duration_since(self.last_update)
-            .unwrap_or(std::time::Duration::from_secs(1))
-            .as_secs_f64();
+fn is_disabled(config: &UserConfig, name: &str) -> bool {
+    config
+        .disabled_collectors
+        .iter()
+        .any(|disabled| disabled == name)
+}
 
-        let received_rate = if time_delta > 0.0 {
-            ((total_received.saturating_sub(self.previous_network.0) as f64) / time_delta) as f32
-        } else {
-            0.0
-        };
+impl Default for SystemMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        let transmitted_rate = if time_delta > 0.0 {
-            ((total_transmitted.saturating_sub(self.previous_network.1) as f64) / time_delta) as f32
-        } else {
-            0.0
-        };
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
 
-        self.previous_network = (total_received, total_transmitted);
+    struct FakeCpuCollector {
+        usage: f32,
+    }
+
+    impl MetricCollector for FakeCpuCollector {
+        fn name(&self) -> &'static str {
+            "fake-cpu"
+        }
 
-        NetworkMetrics {
-            received_bytes: total_received,
-            transmitted_bytes: total_transmitted,
-            received_rate,
-            transmitted_rate,
+        fn collect(&mut self, _ctx: &CollectContext, metrics: &mut SystemMetrics) {
+            metrics.cpu.usage_percentage = self.usage;
         }
     }
 
-    fn collect_disk_metrics(&mut self, now: SystemTime) -> DiskMetrics {
-        let total_read = 0u64;
-        let total_written = 0u64;
+    #[test]
+    fn test_new_system_monitor() {
+        let monitor = SystemMonitor::new();
+        assert_eq!(
+            monitor.collector_names(),
+            vec![
+                "cpu",
+                "memory",
+                "cgroup",
+                "network",
+                "disk",
+                "filesystems",
+                "load",
+                "pressure",
+                "sensors",
+                "battery"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_process_collector_only_when_shown() {
+        let config = UserConfig {
+            show_processes: true,
+            ..UserConfig::default()
+        };
 
-        let time_delta = now
-            .duration_since(self.last_update)
-            .unwrap_or(std::time::Duration::from_secs(1))
-            .as_secs_f64();
+        let monitor = SystemMonitor::from_config(&config);
+        assert!(monitor.collector_names().contains(&"processes"));
+    }
 
-        let read_rate = if time_delta > 0.0 {
-            (total_read.saturating_sub(self.previous_disk.0) as f64 / time_delta) as f32
-        } else {
-            0.0
+    #[test]
+    fn test_unit_collector_only_when_shown() {
+        assert!(!SystemMonitor::new().collector_names().contains(&"units"));
+
+        let config = UserConfig {
+            show_units: true,
+            ..UserConfig::default()
         };
+        let monitor = SystemMonitor::from_config(&config);
+        assert!(monitor.collector_names().contains(&"units"));
+    }
 
-        let write_rate = if time_delta > 0.0 {
-            (total_written.saturating_sub(self.previous_disk.1) as f64 / time_delta) as f32
-        } else {
-            0.0
+    #[test]
+    fn test_apply_config_follows_shown_sections() {
+        let mut config = UserConfig::default();
+        let mut monitor = SystemMonitor::from_config(&config);
+
+        config.show_processes = true;
+        config.show_units = true;
+        monitor.apply_config(&config);
+        monitor.apply_config(&config);
+        let names = monitor.collector_names();
+        assert_eq!(names.iter().filter(|&&name| name == "processes").count(), 1);
+        assert!(names.contains(&"units"));
+
+        config.show_processes = false;
+        config.disabled_collectors = vec!["units".to_string()];
+        monitor.apply_config(&config);
+        let names = monitor.collector_names();
+        assert!(!names.contains(&"processes"));
+        assert!(!names.contains(&"units"));
+    }
+
+    #[test]
+    fn test_apply_config_rebuilds_changed_collectors() {
+        let mut config = UserConfig {
+            show_processes: true,
+            show_units: true,
+            process_count: 20,
+            ..UserConfig::default()
         };
+        let mut monitor = SystemMonitor::from_config(&config);
+
+        config.process_count = 1;
+        config.unit_cgroup = "user.slice".to_string();
+        monitor.apply_config(&config);
+
+        let names = monitor.collector_names();
+        assert_eq!(names.iter().filter(|&&name| name == "processes").count(), 1);
+        assert_eq!(names.iter().filter(|&&name| name == "units").count(), 1);
+        assert_eq!(monitor.optional_settings["units"], "user.slice");
+        // The top process by CPU and by memory, at most.
+        assert!(monitor.collect().processes.len() <= 2);
+    }
 
-        self.previous_disk = (total_read, total_written);
+    #[test]
+    fn test_disabled_collectors_are_skipped() {
+        let config = UserConfig {
+            disabled_collectors: vec![
+                "cgroup".to_string(),
+                "network".to_string(),
+                "disk".to_string(),
+                "filesystems".to_string(),
+                "load".to_string(),
+                "pressure".to_string(),
+                "sensors".to_string(),
+                "battery".to_string(),
+            ],
+            ..UserConfig::default()
+        };
+
+        let monitor = SystemMonitor::from_config(&config);
+        assert_eq!(monitor.collector_names(), vec!["cpu", "memory"]);
+    }
+
+    #[test]
+    fn test_fake_collector_fills_snapshot() {
+        let mut monitor = SystemMonitor::with_collectors(Vec::new());
+        monitor.register(Box::new(FakeCpuCollector { usage: 42.0 }));
+
+        let metrics = monitor.collect();
 
-        DiskMetrics {
-            read_bytes: total_read,
-            written_bytes: total_written,
-            read_rate,
-            write_rate,
+        assert_eq!(metrics.cpu.usage_percentage, 42.0);
+        assert_eq!(metrics.memory.total_bytes, 0);
+    }
+
+    /// A clock the test moves by hand. Both halves share one handle so the
+    /// test can keep advancing it after the monitor has taken ownership.
+    #[derive(Clone)]
+    struct FakeClock {
+        now: Arc<Mutex<(Duration, SystemTime)>>,
+    }
+
+    impl FakeClock {
+        fn new() -> Self {
+            Self {
+                now: Arc::new(Mutex::new((Duration::ZERO, SystemTime::UNIX_EPOCH))),
+            }
+        }
+
+        fn advance(&self, monotonic: Duration, wall: Duration) {
+            let mut now = self.now.lock().unwrap();
+            now.0 += monotonic;
+            now.1 += wall;
         }
     }
-}
 
-impl Default for SystemMonitor {
-    fn default() -> Self {
-        Self::new()
+    impl Clock for FakeClock {
+        fn monotonic(&self) -> Duration {
+            self.now.lock().unwrap().0
+        }
+
+        fn wall(&self) -> SystemTime {
+            self.now.lock().unwrap().1
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::time::Duration;
+    struct ElapsedCollector {
+        seen: Arc<Mutex<Vec<f64>>>,
+    }
+
+    impl MetricCollector for ElapsedCollector {
+        fn name(&self) -> &'static str {
+            "elapsed"
+        }
+
+        fn collect(&mut self, ctx: &CollectContext, _metrics: &mut SystemMetrics) {
+            self.seen.lock().unwrap().push(ctx.elapsed_secs);
+        }
+    }
 
     #[test]
-    fn test_new_system_monitor() {
-        let monitor = SystemMonitor::new();
-        assert_eq!(monitor.previous_network, (0, 0));
-        assert_eq!(monitor.previous_disk, (0, 0));
+    fn test_elapsed_time_follows_monotonic_clock() {
+        let clock = FakeClock::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let collector = ElapsedCollector {
+            seen: Arc::clone(&seen),
+        };
+        let mut monitor =
+            SystemMonitor::with_clock(vec![Box::new(collector)], Box::new(clock.clone()));
+
+        clock.advance(Duration::from_secs(2), Duration::from_secs(2));
+        let first = monitor.collect();
+
+        // The wall clock being set back an hour doesn't affect rates.
+        clock.advance(Duration::from_millis(500), Duration::ZERO);
+        clock.now.lock().unwrap().1 -= Duration::from_secs(3600);
+        let second = monitor.collect();
+
+        assert_eq!(*seen.lock().unwrap(), vec![2.0, 0.5]);
+        assert_eq!(
+            first.timestamp,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(2)
+        );
+        assert!(!first.gap_after_suspend);
+        assert!(!second.gap_after_suspend);
+    }
+
+    #[test]
+    fn test_suspend_marks_gap() {
+        let clock = FakeClock::new();
+        let mut monitor = SystemMonitor::with_clock(Vec::new(), Box::new(clock.clone()));
+
+        clock.advance(Duration::from_secs(1), Duration::from_secs(1));
+        assert!(!monitor.collect().gap_after_suspend);
+
+        clock.advance(Duration::from_secs(1), Duration::from_secs(40 * 60));
+        assert!(monitor.collect().gap_after_suspend);
+
+        clock.advance(Duration::from_secs(1), Duration::from_secs(1));
+        assert!(!monitor.collect().gap_after_suspend);
     }
 
     #[test]
@@ -181,15 +437,70 @@ mod tests {
     }
 
     #[test]
-    fn test_network_bytes_are_cumulative() {
+    fn test_disk_totals_are_sum_of_devices() {
         let mut monitor = SystemMonitor::new();
+        let metrics = monitor.collect();
 
-        let first = monitor.collect();
-        std::thread::sleep(Duration::from_secs(1));
-        let second = monitor.collect();
+        let read: u64 = metrics.disk.devices.iter().map(|d| d.read_bytes).sum();
+        let written: u64 = metrics.disk.devices.iter().map(|d| d.written_bytes).sum();
 
-        assert!(second.network.received_bytes >= first.network.received_bytes);
-        assert!(second.network.transmitted_bytes >= first.network.transmitted_bytes);
+        assert_eq!(metrics.disk.read_bytes, read);
+        assert_eq!(metrics.disk.written_bytes, written);
+    }
+
+    /// CPU time used by every thread of the test binary. sysinfo refreshes
+    /// processes on a thread pool, so the sampling thread's time alone would
+    /// flatter `refresh_all`.
+    #[cfg(unix)]
+    fn process_cpu_time() -> Duration {
+        let mut ts = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        // SAFETY: `ts` is a valid, writable timespec for the duration of the call.
+        let result = unsafe { libc::clock_gettime(libc::CLOCK_PROCESS_CPUTIME_ID, &mut ts) };
+        assert_eq!(result, 0);
+        Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[ignore = "measures CPU time across the whole process, so run it alone with --ignored"]
+    fn test_sample_costs_less_than_refresh_all() {
+        const SAMPLES: u32 = 10;
+
+        let mut monitor = SystemMonitor::new();
+        let mut system = sysinfo::System::new_all();
+        monitor.collect();
+        system.refresh_all();
+
+        let before = process_cpu_time();
+        let mut reported = Duration::ZERO;
+        for _ in 0..SAMPLES {
+            reported += monitor.collect().sample_cpu_time;
+        }
+        let scoped = process_cpu_time() - before;
+
+        let before = process_cpu_time();
+        for _ in 0..SAMPLES {
+            system.refresh_all();
+        }
+        let full = process_cpu_time() - before;
+
+        // The sampling thread's own time is part of the whole process's.
+        assert!(reported > Duration::ZERO);
+        assert!(
+            reported <= scoped,
+            "reported {:?}, measured {:?}",
+            reported,
+            scoped
+        );
+        assert!(
+            scoped < full,
+            "collect took {:?} per sample, refresh_all {:?}",
+            scoped / SAMPLES,
+            full / SAMPLES
+        );
     }
 
     #[test]
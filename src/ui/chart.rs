@@ -0,0 +1,167 @@
+use crate::services::format_clock_time;
+use egui::{Color32, Pos2, Rect, Response, Stroke, Ui};
+use std::time::{Duration, SystemTime};
+
+/// Space left of the plot for the value axis labels.
+const VALUE_AXIS_WIDTH: f32 = 64.0;
+/// Space under the plot for the time axis labels.
+const TIME_AXIS_HEIGHT: f32 = 16.0;
+const GRID_DIVISIONS: u32 = 4;
+
+/// One line on a chart.
+pub struct ChartSeries {
+    pub name: String,
+    pub color: Color32,
+    /// Samples oldest first, as recorded by `MetricHistory`.
+    pub samples: Vec<(SystemTime, f32)>,
+}
+
+/// A line chart of the `range` leading up to `now`, with labelled axes. All
+/// series share one vertical scale: `max` when given (e.g. 100 for
+/// percentages), otherwise the largest value shown. Hovering draws a
+/// crosshair and lists each series' sample nearest the pointer.
+pub fn time_series_chart(
+    ui: &mut Ui,
+    series: &[ChartSeries],
+    now: SystemTime,
+    range: Duration,
+    max: Option<f32>,
+    height: f32,
+    format_value: impl Fn(f32) -> String,
+) -> Response {
+    let size = egui::vec2(ui.available_width(), height);
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let plot = Rect::from_min_max(
+        egui::pos2(rect.left() + VALUE_AXIS_WIDTH, rect.top() + 4.0),
+        egui::pos2(rect.right() - 4.0, rect.bottom() - TIME_AXIS_HEIGHT),
+    );
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+    let font = egui::TextStyle::Small.resolve(ui.style());
+    let grid = Stroke::new(1.0, visuals.widgets.noninteractive.bg_stroke.color);
+    let axis_text = visuals.weak_text_color();
+
+    let start = now.checked_sub(range).unwrap_or(SystemTime::UNIX_EPOCH);
+    let visible = |samples: &[(SystemTime, f32)]| -> usize {
+        samples.partition_point(|(time, _)| *time < start)
+    };
+
+    let peak = series
+        .iter()
+        .flat_map(|s| {
+            s.samples[visible(&s.samples)..]
+                .iter()
+                .map(|(_, value)| *value)
+        })
+        .fold(0.0, f32::max);
+    let scale = max.unwrap_or(peak).max(f32::EPSILON);
+
+    let x_at = |time: SystemTime| {
+        let offset = time.duration_since(start).unwrap_or_default().as_secs_f32();
+        plot.left() + offset / range.as_secs_f32().max(f32::EPSILON) * plot.width()
+    };
+    let y_at = |value: f32| plot.bottom() - (value / scale).clamp(0.0, 1.0) * plot.height();
+
+    painter.rect_filled(plot, 0.0, visuals.extreme_bg_color);
+
+    for i in 0..=GRID_DIVISIONS {
+        let fraction = i as f32 / GRID_DIVISIONS as f32;
+
+        let y = plot.bottom() - fraction * plot.height();
+        painter.hline(plot.x_range(), y, grid);
+        painter.text(
+            egui::pos2(plot.left() - 4.0, y),
+            egui::Align2::RIGHT_CENTER,
+            format_value(scale * fraction),
+            font.clone(),
+            axis_text,
+        );
+
+        let x = plot.left() + fraction * plot.width();
+        painter.vline(x, plot.y_range(), grid);
+        // Keep the outermost labels inside the chart.
+        let align = match i {
+            0 => egui::Align2::LEFT_TOP,
+            i if i == GRID_DIVISIONS => egui::Align2::RIGHT_TOP,
+            _ => egui::Align2::CENTER_TOP,
+        };
+        painter.text(
+            egui::pos2(x, plot.bottom() + 2.0),
+            align,
+            format_clock_time(start + range.mul_f32(fraction)),
+            font.clone(),
+            axis_text,
+        );
+    }
+
+    let plot_painter = painter.with_clip_rect(plot);
+    for line in series {
+        let points: Vec<Pos2> = line.samples[visible(&line.samples)..]
+            .iter()
+            .map(|&(time, value)| egui::pos2(x_at(time), y_at(value)))
+            .collect();
+        plot_painter.add(egui::Shape::line(points, Stroke::new(1.5, line.color)));
+    }
+
+    let Some(pointer) = response.hover_pos().filter(|pos| plot.contains(*pos)) else {
+        return response;
+    };
+
+    let crosshair = Stroke::new(1.0, visuals.text_color().gamma_multiply(0.5));
+    plot_painter.vline(pointer.x, plot.y_range(), crosshair);
+    plot_painter.hline(plot.x_range(), pointer.y, crosshair);
+    painter.text(
+        egui::pos2(plot.left() - 4.0, pointer.y),
+        egui::Align2::RIGHT_CENTER,
+        format_value((plot.bottom() - pointer.y) / plot.height() * scale),
+        font,
+        visuals.text_color(),
+    );
+
+    let fraction = (pointer.x - plot.left()) / plot.width();
+    let time = start + range.mul_f32(fraction.clamp(0.0, 1.0));
+    let readings: Vec<(&ChartSeries, SystemTime, f32)> = series
+        .iter()
+        .filter_map(|line| {
+            let (when, value) = nearest(&line.samples[visible(&line.samples)..], time)?;
+            Some((line, when, value))
+        })
+        .collect();
+
+    for &(line, when, value) in &readings {
+        plot_painter.circle_filled(egui::pos2(x_at(when), y_at(value)), 2.5, line.color);
+    }
+
+    response.on_hover_ui_at_pointer(|ui| {
+        ui.label(
+            egui::RichText::new(format_clock_time(time))
+                .monospace()
+                .strong(),
+        );
+        for (line, _, value) in readings {
+            ui.label(
+                egui::RichText::new(format!("{:<10}{:>12}", line.name, format_value(value)))
+                    .monospace()
+                    .color(line.color),
+            );
+        }
+    })
+}
+
+/// The sample closest in time to `time`, from samples sorted oldest first.
+fn nearest(samples: &[(SystemTime, f32)], time: SystemTime) -> Option<(SystemTime, f32)> {
+    let index = samples.partition_point(|(when, _)| *when < time);
+    let distance = |(when, _): &(SystemTime, f32)| {
+        when.duration_since(time)
+            .or_else(|_| time.duration_since(*when))
+            .unwrap_or_default()
+    };
+
+    let before = index.checked_sub(1).and_then(|i| samples.get(i));
+    let after = samples.get(index);
+    match (before, after) {
+        (Some(before), Some(after)) if distance(before) <= distance(after) => Some(*before),
+        (_, Some(after)) => Some(*after),
+        (before, None) => before.copied(),
+    }
+}
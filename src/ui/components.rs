@@ -1,28 +1,35 @@
 use egui::{Color32, ProgressBar, Response, Ui};
 
-pub fn metric_row(
-    ui: &mut Ui,
-    label: &str,
-    value: &str,
-    color: Color32,
-) -> Response {
+#[allow(dead_code)]
+pub fn metric_row(ui: &mut Ui, label: &str, value: &str, color: Color32) -> Response {
     ui.horizontal(|ui| {
-        ui.label(egui::RichText::new(label).family(egui::FontFamily::Monospace).monospace().color(Color32::GRAY));
+        ui.label(
+            egui::RichText::new(label)
+                .family(egui::FontFamily::Monospace)
+                .monospace()
+                .color(Color32::GRAY),
+        );
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-            ui.label(egui::RichText::new(value).family(egui::FontFamily::Monospace).monospace().strong().color(color));
+            ui.label(
+                egui::RichText::new(value)
+                    .family(egui::FontFamily::Monospace)
+                    .monospace()
+                    .strong()
+                    .color(color),
+            );
         });
     })
-        .response
+    .response
 }
 
-pub fn progress_bar_with_text(
-    ui: &mut Ui,
-    label: &str,
-    fraction: f32,
-    text: &str,
-    color: Color32,
-) {
-    ui.label(egui::RichText::new(label).family(egui::FontFamily::Monospace).monospace().small());
+#[allow(dead_code)]
+pub fn progress_bar_with_text(ui: &mut Ui, label: &str, fraction: f32, text: &str, color: Color32) {
+    ui.label(
+        egui::RichText::new(label)
+            .family(egui::FontFamily::Monospace)
+            .monospace()
+            .small(),
+    );
     ui.add(
         ProgressBar::new(fraction)
             .fill(color)
@@ -31,26 +38,27 @@ pub fn progress_bar_with_text(
     );
 }
 
+#[allow(dead_code)]
 pub fn section_header(ui: &mut Ui, text: &str) {
     ui.add_space(6.0);
-    ui.label(egui::RichText::new(text).family(egui::FontFamily::Monospace).strong().size(12.0));
+    ui.label(
+        egui::RichText::new(text)
+            .family(egui::FontFamily::Monospace)
+            .strong()
+            .size(12.0),
+    );
     ui.separator();
     ui.add_space(2.0);
 }
 
-pub fn compact_metric(
-    ui: &mut Ui,
-    label: &str,
-    value: &str,
-    color: Color32,
-) {
+pub fn compact_metric(ui: &mut Ui, label: &str, value: &str, color: Color32) {
     ui.horizontal(|ui| {
         ui.label(
             egui::RichText::new(label)
                 .family(egui::FontFamily::Monospace)
                 .monospace()
                 .color(Color32::GRAY)
-                .size(11.0)
+                .size(11.0),
         );
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
             ui.label(
@@ -59,25 +67,20 @@ pub fn compact_metric(
                     .monospace()
                     .strong()
                     .color(color)
-                    .size(11.0)
+                    .size(11.0),
             );
         });
     });
 }
 
-pub fn compact_percentage_bar(
-    ui: &mut Ui,
-    label: &str,
-    fraction: f32,
-    color: Color32,
-) {
+pub fn compact_percentage_bar(ui: &mut Ui, label: &str, fraction: f32, color: Color32) {
     ui.horizontal(|ui| {
         ui.label(
             egui::RichText::new(label)
                 .family(egui::FontFamily::Monospace)
                 .monospace()
                 .size(10.0)
-                .color(Color32::GRAY)
+                .color(Color32::GRAY),
         );
         ui.add(
             ProgressBar::new(fraction)
@@ -85,25 +88,19 @@ pub fn compact_percentage_bar(
                 .desired_height(ui.available_width() / 10.0)
                 .desired_width(ui.available_width())
                 .show_percentage()
-                .animate(false)
+                .animate(false),
         );
     });
 }
 
-pub fn compact_text_bar(
-    ui: &mut Ui,
-    label: &str,
-    fraction: f32,
-    color: Color32,
-    text: &str,
-) {
+pub fn compact_text_bar(ui: &mut Ui, label: &str, fraction: f32, color: Color32, text: &str) {
     ui.horizontal(|ui| {
         ui.label(
             egui::RichText::new(label)
                 .family(egui::FontFamily::Monospace)
                 .monospace()
                 .size(10.0)
-                .color(Color32::GRAY)
+                .color(Color32::GRAY),
         );
         ui.add(
             ProgressBar::new(fraction)
@@ -111,7 +108,105 @@ pub fn compact_text_bar(
                 .desired_height(ui.available_width() / 10.0)
                 .desired_width(ui.available_width())
                 .text(text)
-                .animate(false)
+                .animate(false),
         );
     });
 }
+
+/// A bar split into coloured segments, each given as a fraction of the full
+/// width. Whatever the segments don't cover is left as the empty track.
+pub fn compact_stacked_bar(
+    ui: &mut Ui,
+    label: &str,
+    segments: &[(f32, Color32)],
+    text: &str,
+) -> Response {
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new(label)
+                .family(egui::FontFamily::Monospace)
+                .monospace()
+                .size(10.0)
+                .color(Color32::GRAY),
+        );
+
+        let size = egui::vec2(ui.available_width(), ui.available_width() / 10.0);
+        let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        let visuals = ui.visuals();
+
+        painter.rect_filled(rect, 0.0, visuals.extreme_bg_color);
+
+        let mut left = rect.left();
+        for &(fraction, color) in segments {
+            let width = fraction.clamp(0.0, 1.0) * rect.width();
+            let right = (left + width).min(rect.right());
+            if right > left {
+                painter.rect_filled(
+                    egui::Rect::from_min_max(
+                        egui::pos2(left, rect.top()),
+                        egui::pos2(right, rect.bottom()),
+                    ),
+                    0.0,
+                    color,
+                );
+            }
+            left = right;
+        }
+
+        painter.text(
+            egui::pos2(rect.left() + 4.0, rect.center().y),
+            egui::Align2::LEFT_CENTER,
+            text,
+            egui::TextStyle::Button.resolve(ui.style()),
+            visuals.text_color(),
+        );
+
+        response
+    })
+    .inner
+}
+
+/// A small line graph of recent values, newest at the right. Every series
+/// shares one vertical scale: `max` when given (e.g. 100 for percentages),
+/// otherwise the largest value shown.
+pub fn sparkline(ui: &mut Ui, series: &[(&[f32], Color32)], max: Option<f32>) -> Response {
+    let size = egui::vec2(ui.available_width(), ui.available_width() / 10.0);
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+
+    let count = series
+        .iter()
+        .map(|(values, _)| values.len())
+        .max()
+        .unwrap_or(0);
+    if count < 2 {
+        return response;
+    }
+
+    let peak = series
+        .iter()
+        .flat_map(|(values, _)| values.iter().copied())
+        .fold(0.0, f32::max);
+    let scale = max.unwrap_or(peak).max(f32::EPSILON);
+    let step = rect.width() / (count - 1) as f32;
+
+    for (values, color) in series {
+        let offset = count - values.len();
+        let points: Vec<egui::Pos2> = values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let fraction = (value / scale).clamp(0.0, 1.0);
+                egui::pos2(
+                    rect.left() + (offset + i) as f32 * step,
+                    rect.bottom() - fraction * rect.height(),
+                )
+            })
+            .collect();
+
+        painter.add(egui::Shape::line(points, egui::Stroke::new(1.0, *color)));
+    }
+
+    response
+}
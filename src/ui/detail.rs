@@ -0,0 +1,195 @@
+use crate::models::{Metric, MetricHistory, SystemMetrics, TimeRange, UserConfig};
+use crate::services::format_rate;
+use crate::ui::ColorScheme;
+use crate::ui::chart::{ChartSeries, time_series_chart};
+use egui::{Color32, Context, Ui};
+
+const CHART_HEIGHT: f32 = 180.0;
+
+/// The widget section a detail window is showing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailSection {
+    Cpu,
+    Memory,
+    Network,
+    Disk,
+}
+
+impl DetailSection {
+    /// The section whose charts show `metric`. Metrics without charts of
+    /// their own open on the CPU charts.
+    pub fn for_metric(metric: Metric) -> Self {
+        match metric {
+            Metric::MemoryUsage | Metric::SwapUsage | Metric::MemoryPressure => {
+                DetailSection::Memory
+            }
+            Metric::NetworkReceived | Metric::NetworkTransmitted => DetailSection::Network,
+            Metric::DiskRead | Metric::DiskWrite | Metric::IoPressure => DetailSection::Disk,
+            Metric::CpuUsage
+            | Metric::CoreUsage(_)
+            | Metric::CpuPressure
+            | Metric::LoadOne
+            | Metric::Temperature
+            | Metric::BatteryCharge => DetailSection::Cpu,
+        }
+    }
+
+    const ALL: [DetailSection; 4] = [
+        DetailSection::Cpu,
+        DetailSection::Memory,
+        DetailSection::Network,
+        DetailSection::Disk,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            DetailSection::Cpu => "cpu",
+            DetailSection::Memory => "mem",
+            DetailSection::Network => "net",
+            DetailSection::Disk => "disk",
+        }
+    }
+}
+
+/// Shows the detail window for `section` in its own viewport, raising it
+/// when `focus` is set. Returns `false` once the user has closed it.
+pub fn render_detail(
+    ctx: &Context,
+    section: &mut DetailSection,
+    metrics: &SystemMetrics,
+    history: &MetricHistory,
+    config: &mut UserConfig,
+    focus: bool,
+) -> bool {
+    let colors = ColorScheme::from_theme(config.theme);
+    let builder = egui::ViewportBuilder::default()
+        .with_title("perch details")
+        .with_inner_size([640.0, 480.0]);
+
+    ctx.show_viewport_immediate(
+        egui::ViewportId::from_hash_of("perch_detail"),
+        builder,
+        |ctx, class| {
+            if class == egui::ViewportClass::Embedded {
+                let mut open = true;
+                egui::Window::new("perch details")
+                    .open(&mut open)
+                    .show(ctx, |ui| {
+                        render_contents(ui, section, metrics, history, &colors, config);
+                    });
+                return open;
+            }
+
+            if focus {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+            }
+            egui::CentralPanel::default().show(ctx, |ui| {
+                render_contents(ui, section, metrics, history, &colors, config);
+            });
+            !ctx.input(|i| i.viewport().close_requested())
+        },
+    )
+}
+
+fn render_contents(
+    ui: &mut Ui,
+    section: &mut DetailSection,
+    metrics: &SystemMetrics,
+    history: &MetricHistory,
+    colors: &ColorScheme,
+    config: &mut UserConfig,
+) {
+    ui.horizontal(|ui| {
+        for option in DetailSection::ALL {
+            ui.selectable_value(section, option, option.label());
+        }
+        ui.separator();
+        for range in TimeRange::ALL {
+            ui.selectable_value(&mut config.detail_range, range, range.label());
+        }
+    });
+    ui.separator();
+
+    let chart = |ui: &mut Ui,
+                 title: &str,
+                 lines: &[(&str, Metric, Color32)],
+                 max: Option<f32>,
+                 format: fn(f32) -> String| {
+        let range = config.detail_range.duration();
+        let series: Vec<ChartSeries> = lines
+            .iter()
+            .map(|&(name, metric, color)| ChartSeries {
+                name: name.to_string(),
+                color,
+                samples: history.samples(metric, range),
+            })
+            .collect();
+
+        ui.label(egui::RichText::new(title).monospace().strong());
+        time_series_chart(
+            ui,
+            &series,
+            metrics.timestamp,
+            range,
+            max,
+            CHART_HEIGHT,
+            format,
+        );
+        ui.add_space(8.0);
+    };
+
+    egui::ScrollArea::vertical().show(ui, |ui| match *section {
+        DetailSection::Cpu => {
+            let total = [("total", Metric::CpuUsage, colors.primary)];
+            chart(ui, "cpu", &total, Some(100.0), percentage);
+
+            let cores: Vec<(String, Metric, Color32)> = metrics
+                .cpu
+                .per_core
+                .iter()
+                .enumerate()
+                .map(|(i, core)| {
+                    (
+                        format!("cpu{}", core.id),
+                        Metric::CoreUsage(core.id),
+                        colors.series_color(i),
+                    )
+                })
+                .collect();
+            let lines: Vec<(&str, Metric, Color32)> = cores
+                .iter()
+                .map(|(name, metric, color)| (name.as_str(), *metric, *color))
+                .collect();
+            chart(ui, "per core", &lines, Some(100.0), percentage);
+        }
+        DetailSection::Memory => {
+            let mut lines = vec![("memory", Metric::MemoryUsage, colors.secondary)];
+            if metrics.memory.swap_total_bytes > 0 {
+                lines.push(("swap", Metric::SwapUsage, colors.warning));
+            }
+            chart(ui, "memory", &lines, Some(100.0), percentage);
+        }
+        DetailSection::Network => {
+            let lines = [
+                ("rx", Metric::NetworkReceived, colors.primary),
+                ("tx", Metric::NetworkTransmitted, colors.secondary),
+            ];
+            chart(ui, "network", &lines, None, rate);
+        }
+        DetailSection::Disk => {
+            let lines = [
+                ("read", Metric::DiskRead, colors.success),
+                ("write", Metric::DiskWrite, colors.warning),
+            ];
+            chart(ui, "disk", &lines, None, rate);
+        }
+    });
+}
+
+fn percentage(value: f32) -> String {
+    format!("{:.0}%", value)
+}
+
+fn rate(value: f32) -> String {
+    format_rate(value as u64)
+}
@@ -1,7 +1,12 @@
+pub mod chart;
 pub mod components;
+pub mod detail;
 pub mod theme;
 pub mod widget;
 
-use components::{metric_row, progress_bar_with_text, section_header, compact_text_bar, compact_percentage_bar, compact_metric};
+use components::{
+    compact_metric, compact_percentage_bar, compact_stacked_bar, compact_text_bar, sparkline,
+};
+pub use detail::{DetailSection, render_detail};
 pub use theme::ColorScheme;
-pub use widget::render_widget;
+pub use widget::{SUMMARY_WINDOW, render_widget};
@@ -1,11 +1,13 @@
-use crate::models::Theme;
+use crate::models::{CpuState, Severity, Theme};
 use egui::Color32;
 
 pub struct ColorScheme {
     pub background: Color32,
+    #[allow(dead_code)]
     pub surface: Color32,
     pub primary: Color32,
     pub secondary: Color32,
+    #[allow(dead_code)]
     pub text: Color32,
     pub text_dim: Color32,
     pub success: Color32,
@@ -64,6 +66,28 @@ impl ColorScheme {
         }
     }
 
+    /// A colour for the `index`th of several lines on one chart. Colours
+    /// repeat once the scheme's accents run out.
+    pub fn series_color(&self, index: usize) -> Color32 {
+        let palette = [
+            self.primary,
+            self.secondary,
+            self.success,
+            self.warning,
+            self.error,
+            self.text_dim,
+        ];
+        palette[index % palette.len()]
+    }
+
+    pub fn severity_color(&self, severity: Severity) -> Color32 {
+        match severity {
+            Severity::Info => self.primary,
+            Severity::Warning => self.warning,
+            Severity::Critical => self.error,
+        }
+    }
+
     pub fn cpu_color(&self, usage: f32) -> Color32 {
         if usage > 80.0 {
             self.error
@@ -83,4 +107,60 @@ impl ColorScheme {
             self.success
         }
     }
+
+    pub fn battery_color(&self, charge: f32) -> Color32 {
+        if charge < 15.0 {
+            self.error
+        } else if charge < 30.0 {
+            self.warning
+        } else {
+            self.success
+        }
+    }
+
+    /// Colours a PSI average. Sustained stalls above a few percent are
+    /// already noticeable, so the thresholds are much lower than for usage.
+    pub fn pressure_color(&self, avg: f32) -> Color32 {
+        if avg > 25.0 {
+            self.error
+        } else if avg > 5.0 {
+            self.warning
+        } else {
+            self.success
+        }
+    }
+
+    /// Warns as a sensor approaches its own `max` rating and turns red at
+    /// `critical`. Sensors that report no thresholds fall back to 70/90°C.
+    pub fn temperature_color(
+        &self,
+        celsius: f32,
+        max_celsius: Option<f32>,
+        critical_celsius: Option<f32>,
+    ) -> Color32 {
+        let critical = critical_celsius.unwrap_or(90.0);
+        let warning = max_celsius.unwrap_or(critical - 20.0);
+
+        if celsius >= critical {
+            self.error
+        } else if celsius >= warning {
+            self.warning
+        } else {
+            self.success
+        }
+    }
+
+    /// Colour for each state in the CPU time breakdown.
+    pub fn cpu_state_color(&self, state: CpuState) -> Color32 {
+        match state {
+            CpuState::User => self.primary,
+            CpuState::Nice => self.primary.gamma_multiply(0.6),
+            CpuState::System => self.secondary,
+            CpuState::Iowait => self.warning,
+            CpuState::Irq => self.secondary.gamma_multiply(0.6),
+            CpuState::Softirq => self.warning.gamma_multiply(0.6),
+            CpuState::Steal => self.error,
+            CpuState::Guest => self.success,
+        }
+    }
 }
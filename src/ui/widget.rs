@@ -1,24 +1,32 @@
-use crate::models::{SystemMetrics, UserConfig};
-use crate::services::{format_bytes, format_rate};
-use crate::ui::{ColorScheme, metric_row, progress_bar_with_text, section_header, compact_metric, compact_percentage_bar, compact_text_bar};
-use egui::{Context, Ui, Color32, ProgressBar};
+use crate::models::{
+    BatteryState, CgroupMetrics, CoreKind, CpuTimeBreakdown, Metric, MetricHistory, ProcessSort,
+    SystemMetrics, UnitSort, UserConfig, top_processes,
+};
+use crate::services::{
+    RuleStatus, format_boot_time, format_bytes, format_clock_time, format_duration, format_metric,
+    format_rate, format_uptime,
+};
+use crate::ui::{
+    ColorScheme, DetailSection, compact_metric, compact_percentage_bar, compact_stacked_bar,
+    compact_text_bar, sparkline,
+};
+use egui::{Color32, Context, Ui};
 
+/// Window the header's summary statistics are taken over.
+pub const SUMMARY_WINDOW: std::time::Duration = std::time::Duration::from_secs(5 * 60);
 
 pub fn render_widget(
     ctx: &Context,
     metrics: &SystemMetrics,
-    config: &UserConfig,
+    history: &MetricHistory,
+    firing: &[RuleStatus],
+    config: &mut UserConfig,
     show_settings: &mut bool,
+    detail: &mut Option<DetailSection>,
 ) {
     let colors = ColorScheme::from_theme(config.theme);
 
-    let is_hovering = ctx.input(|i| {
-        if let Some(pos) = i.pointer.hover_pos() {
-            true
-        } else {
-            false
-        }
-    });
+    let is_hovering = ctx.input(|i| i.pointer.hover_pos().is_some());
 
     let ui_hovered = ctx.is_pointer_over_area();
 
@@ -56,16 +64,99 @@ pub fn render_widget(
                 ctx.send_viewport_cmd(egui::ViewportCommand::StartDrag);
             }
 
-            render_header(ui, &colors, show_settings);
+            render_header(ui, metrics, history, &colors, show_settings);
+            if !firing.is_empty() {
+                render_alerts(ui, firing, metrics.timestamp, &colors);
+            }
+            if metrics.load.uptime > std::time::Duration::ZERO {
+                render_load(ui, &metrics.load, &colors);
+            }
             ui.add_space(4.0);
-            render_cpu(ui, &metrics.cpu, &colors, config);
-            render_memory(ui, &metrics.memory, &colors);
+            opens_detail(ui, DetailSection::Cpu, detail, |ui| {
+                render_cpu(
+                    ui,
+                    &metrics.cpu,
+                    metrics.cgroup.as_ref(),
+                    history,
+                    &colors,
+                    config,
+                );
+            });
+            opens_detail(ui, DetailSection::Memory, detail, |ui| {
+                let cgroup = metrics.cgroup.as_ref();
+                render_memory(ui, &metrics.memory, cgroup, history, &colors, config);
+            });
+            render_batteries(ui, &metrics.batteries, &colors);
             ui.add_space(4.0);
-            render_network_and_disk(ui, &metrics.network, &metrics.disk, &colors);
+            opens_detail(ui, DetailSection::Network, detail, |ui| {
+                render_network(ui, &metrics.network, history, &colors, config);
+            });
+            opens_detail(ui, DetailSection::Disk, detail, |ui| {
+                render_disk(ui, &metrics.disk, history, &colors, config);
+            });
+
+            if !metrics.filesystems.is_empty() {
+                render_filesystems(ui, &metrics.filesystems, &colors);
+            }
+
+            if !metrics.thermal.sensors.is_empty() || !metrics.thermal.fans.is_empty() {
+                render_thermal(ui, &metrics.thermal, &colors);
+            }
+
+            if config.show_pressure && metrics.pressure.is_available() {
+                render_pressure(ui, &metrics.pressure, &colors);
+            }
+
+            if config.show_processes {
+                render_processes(ui, &metrics.processes, &colors, config);
+            }
+
+            if config.show_units {
+                render_units(ui, &metrics.units, &colors, config);
+            }
         });
 }
 
-fn render_header(ui: &mut Ui, colors: &ColorScheme, show_settings: &mut bool) {
+/// Makes a section clickable, opening its detail window. Widgets inside the
+/// section that handle clicks themselves, like collapsing headers, still
+/// get them first.
+fn opens_detail(
+    ui: &mut Ui,
+    section: DetailSection,
+    detail: &mut Option<DetailSection>,
+    add_contents: impl FnOnce(&mut Ui),
+) {
+    let response = ui
+        .scope(add_contents)
+        .response
+        .interact(egui::Sense::click())
+        .on_hover_cursor(egui::CursorIcon::PointingHand);
+
+    if response.clicked() {
+        *detail = Some(section);
+    }
+}
+
+fn render_header(
+    ui: &mut Ui,
+    metrics: &SystemMetrics,
+    history: &MetricHistory,
+    colors: &ColorScheme,
+    show_settings: &mut bool,
+) {
+    let mut summary = format!(
+        "Last sample took {} µs of CPU",
+        metrics.sample_cpu_time.as_micros()
+    );
+    for (label, metric) in [("cpu", Metric::CpuUsage), ("mem", Metric::MemoryUsage)] {
+        if let Some(stats) = history.stats(metric, SUMMARY_WINDOW) {
+            summary.push_str(&format!(
+                "\n{} 5m: min {:.0}% avg {:.0}% p95 {:.0}% max {:.0}%",
+                label, stats.min, stats.mean, stats.p95, stats.max
+            ));
+        }
+    }
+
     ui.horizontal(|ui| {
         ui.label(
             egui::RichText::new("perch")
@@ -73,14 +164,17 @@ fn render_header(ui: &mut Ui, colors: &ColorScheme, show_settings: &mut bool) {
                 .size(12.0)
                 .strong()
                 .color(colors.primary),
-        );
+        )
+        .on_hover_text(summary);
+
+        render_scope(ui, metrics.cgroup.as_ref(), colors);
 
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
             if ui
                 .small_button(
                     egui::RichText::new("⚙")
                         .family(egui::FontFamily::Monospace)
-                        .size(14.0)
+                        .size(14.0),
                 )
                 .on_hover_text("Settings")
                 .clicked()
@@ -92,34 +186,239 @@ fn render_header(ui: &mut Ui, colors: &ColorScheme, show_settings: &mut bool) {
     ui.separator();
 }
 
-fn render_cpu(ui: &mut Ui, cpu: &crate::models::CPUMetrics, colors: &ColorScheme, config: &UserConfig) {
-    let cpu_color = colors.cpu_color(cpu.usage_percentage);
-    compact_percentage_bar(
-        ui,
-        "CPU",
-        cpu.usage_percentage / 100.0,
-        cpu_color,
+/// A banner listing the alert rules that are firing.
+fn render_alerts(
+    ui: &mut Ui,
+    firing: &[RuleStatus],
+    now: std::time::SystemTime,
+    colors: &ColorScheme,
+) {
+    let worst = firing
+        .iter()
+        .map(|status| status.rule.severity)
+        .max()
+        .unwrap_or_default();
+    let banner = colors.severity_color(worst);
+
+    egui::Frame::default()
+        .fill(banner.gamma_multiply(0.25))
+        .corner_radius(4.0)
+        .inner_margin(4.0)
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            for status in firing {
+                let condition = status.rule.condition;
+                let value = status
+                    .value
+                    .map(|value| format_metric(condition.metric, value))
+                    .unwrap_or_default();
+                let firing_for = now.duration_since(status.since).unwrap_or_default();
+
+                let name = format!("⚠ {}", status.rule.name);
+                let color = colors.severity_color(status.rule.severity);
+                ui.scope(|ui| compact_metric(ui, &name, &value, color))
+                    .response
+                    .on_hover_text(format!(
+                        "{}\nfiring since {} ({} ago)",
+                        condition,
+                        format_clock_time(status.since),
+                        format_duration(firing_for)
+                    ));
+            }
+        });
+    ui.add_space(2.0);
+}
+
+/// Tags the header with whether CPU and memory are host totals or scoped to
+/// the cgroup perch runs in.
+fn render_scope(ui: &mut Ui, cgroup: Option<&CgroupMetrics>, colors: &ColorScheme) {
+    let (text, color, hover) = match cgroup {
+        Some(cgroup) => {
+            let mut hover = format!("Scoped to cgroup {}", cgroup.path);
+            if let Some(cores) = cgroup.cpu_limit_cores {
+                hover.push_str(&format!("\ncpu limit {:.2} cores", cores));
+            }
+            if let Some(limit) = cgroup.memory_limit_bytes {
+                hover.push_str(&format!("\nmemory limit {}", format_bytes(limit)));
+            }
+            ("cgroup", colors.warning, hover)
+        }
+        None => ("host", colors.text_dim, "Host-wide totals".to_string()),
+    };
+
+    ui.label(
+        egui::RichText::new(text)
+            .family(egui::FontFamily::Monospace)
+            .size(10.0)
+            .color(color),
+    )
+    .on_hover_text(hover);
+}
+
+fn render_load(ui: &mut Ui, load: &crate::models::LoadMetrics, colors: &ColorScheme) {
+    let text = format!(
+        "up {} ld {:.2} {:.2} {:.2}",
+        format_uptime(load.uptime),
+        load.load_one,
+        load.load_five,
+        load.load_fifteen
     );
 
+    ui.label(
+        egui::RichText::new(text)
+            .family(egui::FontFamily::Monospace)
+            .monospace()
+            .color(colors.text_dim)
+            .size(10.0),
+    )
+    .on_hover_text(format!(
+        "booted {}\n{} procs, {} threads\n{} running, {} zombie",
+        format_boot_time(load.boot_time),
+        load.process_count,
+        load.thread_count,
+        load.running_count,
+        load.zombie_count
+    ));
+}
+
+fn render_cpu(
+    ui: &mut Ui,
+    cpu: &crate::models::CPUMetrics,
+    cgroup: Option<&CgroupMetrics>,
+    history: &MetricHistory,
+    colors: &ColorScheme,
+    config: &UserConfig,
+) {
+    let quota = cgroup.and_then(|cgroup| Some((cgroup, cgroup.cpu_limit_cores?)));
+
+    if let Some((cgroup, cores)) = quota {
+        let usage = cgroup.cpu_usage_percentage;
+        compact_text_bar(
+            ui,
+            "CPU",
+            usage / 100.0,
+            colors.cpu_color(usage),
+            &format!("{:.0}% of {:.1} cores", usage, cores),
+        );
+    } else if cpu.breakdown.is_empty() {
+        let cpu_color = colors.cpu_color(cpu.usage_percentage);
+        compact_percentage_bar(ui, "CPU", cpu.usage_percentage / 100.0, cpu_color);
+    } else {
+        breakdown_bar(
+            ui,
+            "CPU",
+            &cpu.breakdown,
+            &format!("{:.0}%", cpu.usage_percentage),
+            colors,
+        );
+    }
+
+    if config.sparklines.cpu {
+        let series = [(Metric::CpuUsage, colors.primary)];
+        render_sparkline(ui, history, &series, Some(100.0), config.sparkline_samples);
+    }
+
     if config.show_cpu_per_core && !cpu.per_core.is_empty() {
         ui.add_space(2.0);
         ui.collapsing("cores", |ui| {
-            for (i, core_usage) in cpu.per_core.iter().enumerate() {
-                let core_color = colors.cpu_color(*core_usage);
-                compact_percentage_bar(
-                    ui,
-                    &format!("C{}", i),
-                    *core_usage / 100.0,
-                    core_color,
-                );
+            let groups = cpu.core_groups();
+            let multi_package = groups.iter().any(|g| g.package_id != groups[0].package_id);
+
+            for group in &groups {
+                let kind = match group.kind {
+                    CoreKind::Performance => "P",
+                    CoreKind::Efficiency => "E",
+                    CoreKind::Unknown => "C",
+                };
+                let label = if multi_package {
+                    format!("{}{}.{}", kind, group.package_id, group.core_id)
+                } else {
+                    format!("{}{}", kind, group.core_id)
+                };
+
+                let usage = group.usage_percentage();
+                let frequency = group.frequency_mhz();
+                let text = if frequency > 0 {
+                    format!("{:.0}% {:.1}GHz", usage, frequency as f32 / 1000.0)
+                } else {
+                    format!("{:.0}%", usage)
+                };
+
+                let breakdown = group.breakdown();
+                if breakdown.is_empty() {
+                    compact_text_bar(ui, &label, usage / 100.0, colors.cpu_color(usage), &text);
+                } else {
+                    breakdown_bar(ui, &label, &breakdown, &text, colors);
+                }
             }
         });
     }
 }
 
-fn render_memory(ui: &mut Ui, memory: &crate::models::MemoryMetrics, colors: &ColorScheme) {
+/// Draws the recent history of one or more metrics as a sparkline.
+fn render_sparkline(
+    ui: &mut Ui,
+    history: &MetricHistory,
+    series: &[(Metric, Color32)],
+    max: Option<f32>,
+    count: usize,
+) {
+    let values: Vec<(Vec<f32>, Color32)> = series
+        .iter()
+        .map(|&(metric, color)| (history.recent(metric, count), color))
+        .collect();
+    let lines: Vec<(&[f32], Color32)> = values
+        .iter()
+        .map(|(values, color)| (values.as_slice(), *color))
+        .collect();
+
+    sparkline(ui, &lines, max);
+}
+
+/// Stacks the busy CPU states into one bar, with the exact split on hover.
+fn breakdown_bar(
+    ui: &mut Ui,
+    label: &str,
+    breakdown: &CpuTimeBreakdown,
+    text: &str,
+    colors: &ColorScheme,
+) {
+    let states = breakdown.busy_states();
+    let segments: Vec<(f32, Color32)> = states
+        .iter()
+        .map(|&(state, pct)| (pct / 100.0, colors.cpu_state_color(state)))
+        .collect();
+
+    compact_stacked_bar(ui, label, &segments, text).on_hover_ui(|ui| {
+        for (state, pct) in states.iter().filter(|(_, pct)| *pct > 0.0) {
+            ui.label(
+                egui::RichText::new(format!("{:<8}{:>5.1}%", state.label(), pct))
+                    .monospace()
+                    .color(colors.cpu_state_color(*state)),
+            );
+        }
+        ui.label(egui::RichText::new(format!("{:<8}{:>5.1}%", "idle", breakdown.idle)).monospace());
+    });
+}
+
+fn render_memory(
+    ui: &mut Ui,
+    memory: &crate::models::MemoryMetrics,
+    cgroup: Option<&CgroupMetrics>,
+    history: &MetricHistory,
+    colors: &ColorScheme,
+    config: &UserConfig,
+) {
     ui.add_space(4.0);
 
+    let limited = cgroup.and_then(|cgroup| {
+        Some((
+            cgroup,
+            cgroup.memory_limit_bytes?,
+            cgroup.memory_usage_percentage?,
+        ))
+    });
+
     let mem_usage = memory.usage_percentage();
     let mem_color = colors.memory_color(mem_usage);
 
@@ -129,13 +428,23 @@ fn render_memory(ui: &mut Ui, memory: &crate::models::MemoryMetrics, colors: &Co
         format_bytes(memory.total_bytes)
     );
 
-    compact_text_bar(
-        ui,
-        "MEM",
-        mem_usage / 100.0,
-        mem_color,
-        &mem_text,
-    );
+    if let Some((cgroup, limit, usage)) = limited {
+        let text = format!(
+            "{}/{}",
+            format_bytes(cgroup.memory_current_bytes),
+            format_bytes(limit)
+        );
+        compact_text_bar(ui, "MEM", usage / 100.0, colors.memory_color(usage), &text);
+    } else if memory.breakdown.is_empty() {
+        compact_text_bar(ui, "MEM", mem_usage / 100.0, mem_color, &mem_text);
+    } else {
+        memory_breakdown_bar(ui, memory, &mem_text, colors);
+    }
+
+    if config.sparklines.memory {
+        let series = [(Metric::MemoryUsage, colors.secondary)];
+        render_sparkline(ui, history, &series, Some(100.0), config.sparkline_samples);
+    }
 
     if memory.swap_total_bytes > 0 {
         let swap_usage = memory.swap_percentage();
@@ -147,21 +456,147 @@ fn render_memory(ui: &mut Ui, memory: &crate::models::MemoryMetrics, colors: &Co
             format_bytes(memory.swap_total_bytes)
         );
 
+        compact_text_bar(ui, "SWAP", swap_usage / 100.0, swap_color, &swap_text)
+    }
+}
+
+/// Splits the memory bar into used, buffers, shared and cache, and lists
+/// the rest of `/proc/meminfo` on hover.
+fn memory_breakdown_bar(
+    ui: &mut Ui,
+    memory: &crate::models::MemoryMetrics,
+    text: &str,
+    colors: &ColorScheme,
+) {
+    let breakdown = &memory.breakdown;
+    let total = memory.total_bytes.max(1) as f32;
+    let used = breakdown.application_bytes(memory.total_bytes);
+
+    let parts = [
+        ("used", used, colors.memory_color(memory.usage_percentage())),
+        ("buffers", breakdown.buffers_bytes, colors.secondary),
+        (
+            "shared",
+            breakdown.shared_bytes,
+            colors.warning.gamma_multiply(0.7),
+        ),
+        (
+            "cache",
+            breakdown.cache_bytes(),
+            colors.primary.gamma_multiply(0.6),
+        ),
+    ];
+    let segments: Vec<(f32, Color32)> = parts
+        .iter()
+        .map(|&(_, bytes, color)| (bytes as f32 / total, color))
+        .collect();
+
+    compact_stacked_bar(ui, "MEM", &segments, text).on_hover_ui(|ui| {
+        let row = |ui: &mut Ui, label: &str, value: String, color: Color32| {
+            ui.label(
+                egui::RichText::new(format!("{:<10}{:>10}", label, value))
+                    .monospace()
+                    .color(color),
+            );
+        };
+
+        for (label, bytes, color) in parts {
+            row(ui, label, format_bytes(bytes), color);
+        }
+        row(
+            ui,
+            "free",
+            format_bytes(breakdown.free_bytes),
+            colors.text_dim,
+        );
+        row(
+            ui,
+            "slab",
+            format_bytes(breakdown.slab_bytes()),
+            colors.text_dim,
+        );
+        row(
+            ui,
+            "dirty",
+            format_bytes(breakdown.dirty_bytes),
+            colors.text_dim,
+        );
+        row(
+            ui,
+            "writeback",
+            format_bytes(breakdown.writeback_bytes),
+            colors.text_dim,
+        );
+
+        if breakdown.hugepages_total_bytes > 0 {
+            let used = breakdown
+                .hugepages_total_bytes
+                .saturating_sub(breakdown.hugepages_free_bytes);
+            let value = format!(
+                "{}/{}",
+                format_bytes(used),
+                format_bytes(breakdown.hugepages_total_bytes)
+            );
+            row(ui, "hugepages", value, colors.text_dim);
+        }
+
+        let compressed = [
+            (
+                "zswap",
+                breakdown.zswap_original_bytes,
+                breakdown.zswap_compressed_bytes,
+            ),
+            (
+                "zram",
+                breakdown.zram_original_bytes,
+                breakdown.zram_compressed_bytes,
+            ),
+        ];
+        for (label, original, compressed) in compressed {
+            if original > 0 {
+                let value = format!("{}→{}", format_bytes(original), format_bytes(compressed));
+                row(ui, label, value, colors.text_dim);
+            }
+        }
+    });
+}
+
+fn render_batteries(
+    ui: &mut Ui,
+    batteries: &[crate::models::BatteryMetrics],
+    colors: &ColorScheme,
+) {
+    for battery in batteries {
+        let mut text = format!("{:.0}%", battery.charge_percentage);
+
+        match battery.state {
+            BatteryState::Charging => text.push_str(" ⚡"),
+            BatteryState::Full => text.push_str(" full"),
+            _ => {}
+        }
+        if let Some(watts) = battery.power_watts.filter(|&w| w > 0.0) {
+            text.push_str(&format!(" {:.1}W", watts));
+        }
+        if let Some(remaining) = battery.time_to_full.or(battery.time_to_empty) {
+            text.push_str(&format!(" {}", format_duration(remaining)));
+        }
+
         compact_text_bar(
             ui,
-            "SWAP",
-            swap_usage / 100.0,
-            swap_color,
-            &swap_text,
-        )
+            "BAT",
+            battery.charge_percentage / 100.0,
+            colors.battery_color(battery.charge_percentage),
+            &text,
+        );
     }
 }
 
-fn render_network_and_disk(
+fn render_network(
     ui: &mut Ui,
     network: &crate::models::NetworkMetrics,
-    disk: &crate::models::DiskMetrics,
+    history: &MetricHistory,
     colors: &ColorScheme,
+    config: &UserConfig,
 ) {
     ui.horizontal(|ui| {
         ui.label(
@@ -169,48 +604,87 @@ fn render_network_and_disk(
                 .family(egui::FontFamily::Monospace)
                 .monospace()
                 .color(Color32::GRAY)
-                .size(11.0)
+                .size(11.0),
         );
         ui.label(
             egui::RichText::new(format_rate(network.received_rate as u64))
                 .family(egui::FontFamily::Monospace)
                 .monospace()
                 .color(colors.primary)
-                .size(11.0)
+                .size(11.0),
         );
 
-        ui.add_space(8.0);  // Spacer
+        ui.add_space(8.0); // Spacer
 
         ui.label(
             egui::RichText::new("↑")
                 .family(egui::FontFamily::Monospace)
                 .monospace()
                 .color(Color32::GRAY)
-                .size(11.0)
+                .size(11.0),
         );
         ui.label(
             egui::RichText::new(format_rate(network.transmitted_rate as u64))
                 .family(egui::FontFamily::Monospace)
                 .monospace()
                 .color(colors.secondary)
-                .size(11.0)
+                .size(11.0),
         );
     });
 
+    if config.sparklines.network {
+        let series = [
+            (Metric::NetworkReceived, colors.primary),
+            (Metric::NetworkTransmitted, colors.secondary),
+        ];
+        render_sparkline(ui, history, &series, None, config.sparkline_samples);
+    }
+
+    if config.show_network_interfaces && !network.interfaces.is_empty() {
+        ui.collapsing("interfaces", |ui| {
+            let visible = network
+                .interfaces
+                .iter()
+                .filter(|i| config.include_virtual_interfaces || !i.is_virtual);
+
+            for interface in visible {
+                let text = format!(
+                    "↓{} ↑{}",
+                    format_rate(interface.received_rate as u64),
+                    format_rate(interface.transmitted_rate as u64)
+                );
+                let color = if interface.is_up {
+                    colors.primary
+                } else {
+                    colors.text_dim
+                };
+                compact_metric(ui, &interface.name, &text, color);
+            }
+        });
+    }
+}
+
+fn render_disk(
+    ui: &mut Ui,
+    disk: &crate::models::DiskMetrics,
+    history: &MetricHistory,
+    colors: &ColorScheme,
+    config: &UserConfig,
+) {
     ui.horizontal(|ui| {
         ui.label(
             egui::RichText::new("R")
                 .family(egui::FontFamily::Monospace)
                 .monospace()
                 .color(Color32::GRAY)
-                .size(11.0)
+                .size(11.0),
         );
         ui.label(
             egui::RichText::new(format_rate(disk.read_rate as u64))
                 .family(egui::FontFamily::Monospace)
                 .monospace()
                 .color(colors.success)
-                .size(11.0)
+                .size(11.0),
         );
 
         ui.add_space(8.0);
@@ -220,14 +694,215 @@ fn render_network_and_disk(
                 .family(egui::FontFamily::Monospace)
                 .monospace()
                 .color(Color32::GRAY)
-                .size(11.0)
+                .size(11.0),
         );
         ui.label(
             egui::RichText::new(format_rate(disk.write_rate as u64))
                 .family(egui::FontFamily::Monospace)
                 .monospace()
                 .color(colors.warning)
-                .size(11.0)
+                .size(11.0),
         );
     });
+
+    if config.sparklines.disk {
+        let series = [
+            (Metric::DiskRead, colors.success),
+            (Metric::DiskWrite, colors.warning),
+        ];
+        render_sparkline(ui, history, &series, None, config.sparkline_samples);
+    }
+
+    if !disk.devices.is_empty() {
+        ui.collapsing("disks", |ui| {
+            for device in &disk.devices {
+                let text = format!(
+                    "R {} W {}",
+                    format_rate(device.read_rate as u64),
+                    format_rate(device.write_rate as u64)
+                );
+                compact_metric(ui, &device.name, &text, colors.text_dim);
+            }
+        });
+    }
+}
+
+fn render_processes(
+    ui: &mut Ui,
+    processes: &[crate::models::ProcessMetrics],
+    colors: &ColorScheme,
+    config: &mut UserConfig,
+) {
+    ui.add_space(4.0);
+    ui.collapsing(format!("top {}", config.process_count), |ui| {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut config.process_sort, ProcessSort::Cpu, "cpu");
+            ui.selectable_value(&mut config.process_sort, ProcessSort::Memory, "mem");
+        });
+
+        for process in top_processes(processes, config.process_sort, config.process_count) {
+            let (text, color) = match config.process_sort {
+                ProcessSort::Cpu => (
+                    format!("{:.1}%", process.cpu_percentage),
+                    colors.cpu_color(process.cpu_percentage),
+                ),
+                ProcessSort::Memory => (format_bytes(process.rss_bytes), colors.text_dim),
+            };
+            // Several processes can share a name, so the details tell them
+            // apart.
+            let command = if process.command.is_empty() {
+                &process.name
+            } else {
+                &process.command
+            };
+
+            ui.scope(|ui| compact_metric(ui, &process.name, &text, color))
+                .response
+                .on_hover_text(format!(
+                    "pid {} ({})\n{}\ncpu {:.1}%\nmem {}",
+                    process.pid,
+                    process.user,
+                    command,
+                    process.cpu_percentage,
+                    format_bytes(process.rss_bytes)
+                ));
+        }
+    });
+}
+
+fn render_units(
+    ui: &mut Ui,
+    units: &[crate::models::UnitMetrics],
+    colors: &ColorScheme,
+    config: &mut UserConfig,
+) {
+    ui.add_space(4.0);
+    ui.collapsing("units", |ui| {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut config.unit_sort, UnitSort::Cpu, "cpu");
+            ui.selectable_value(&mut config.unit_sort, UnitSort::Memory, "mem");
+            ui.selectable_value(&mut config.unit_sort, UnitSort::Io, "io");
+        });
+
+        let mut sorted: Vec<&crate::models::UnitMetrics> = units.iter().collect();
+        sorted.sort_by(|a, b| a.compare(b, config.unit_sort));
+
+        for unit in sorted.into_iter().take(config.unit_count) {
+            let (text, color) = match config.unit_sort {
+                UnitSort::Cpu => (
+                    format!("{:.1}%", unit.cpu_percentage),
+                    colors.cpu_color(unit.cpu_percentage),
+                ),
+                UnitSort::Memory => (format_bytes(unit.memory_bytes), colors.text_dim),
+                UnitSort::Io => (format_rate(unit.io_rate() as u64), colors.primary),
+            };
+            let name = unit.name.strip_suffix(".service").unwrap_or(&unit.name);
+
+            ui.scope(|ui| compact_metric(ui, name, &text, color))
+                .response
+                .on_hover_text(format!(
+                    "{}\ncpu {:.1}%\nmem {}\nread {} write {}",
+                    unit.name,
+                    unit.cpu_percentage,
+                    format_bytes(unit.memory_bytes),
+                    format_rate(unit.io_read_rate as u64),
+                    format_rate(unit.io_write_rate as u64)
+                ));
+        }
+    });
+}
+
+fn render_filesystems(
+    ui: &mut Ui,
+    filesystems: &[crate::models::FilesystemMetrics],
+    colors: &ColorScheme,
+) {
+    ui.add_space(4.0);
+    ui.collapsing("space", |ui| {
+        for filesystem in filesystems {
+            let usage = filesystem.usage_percentage();
+            let inode_usage = filesystem.inode_percentage();
+            let mut text = format!(
+                "{}/{}",
+                format_bytes(filesystem.used_bytes),
+                format_bytes(filesystem.total_bytes)
+            );
+
+            // Running out of inodes fills a disk just as surely as running
+            // out of space, so colour by whichever is closer to full.
+            if inode_usage > usage {
+                text.push_str(&format!(" i{:.0}%", inode_usage));
+            }
+
+            compact_text_bar(
+                ui,
+                &filesystem.mount_point,
+                usage / 100.0,
+                colors.memory_color(usage.max(inode_usage)),
+                &text,
+            );
+        }
+    });
+}
+
+fn render_pressure(ui: &mut Ui, pressure: &crate::models::PressureMetrics, colors: &ColorScheme) {
+    ui.add_space(4.0);
+
+    ui.collapsing(format!("psi {:.1}%", pressure.worst_avg10()), |ui| {
+        for (name, resource) in pressure.resources() {
+            let Some(resource) = resource else {
+                continue;
+            };
+
+            let lines = [
+                ("some", Some(&resource.some)),
+                ("full", resource.full.as_ref()),
+            ];
+            for (kind, stats) in lines {
+                let Some(stats) = stats else {
+                    continue;
+                };
+                let label = format!("{} {}", name, kind);
+                let value = format!("{:.1} {:.1} {:.1}", stats.avg10, stats.avg60, stats.avg300);
+
+                ui.scope(|ui| {
+                    compact_metric(ui, &label, &value, colors.pressure_color(stats.avg10));
+                })
+                .response
+                .on_hover_text(format!(
+                    "avg10/60/300 %\nstalled {:.1}s in total",
+                    stats.total.as_secs_f64()
+                ));
+            }
+        }
+    });
+}
+
+fn render_thermal(ui: &mut Ui, thermal: &crate::models::ThermalMetrics, colors: &ColorScheme) {
+    ui.add_space(4.0);
+
+    let title = match thermal.hottest() {
+        Some(hottest) => format!("temp {:.0}°C", hottest.current_celsius),
+        None => "fans".to_string(),
+    };
+
+    ui.collapsing(title, |ui| {
+        for sensor in &thermal.sensors {
+            let color = colors.temperature_color(
+                sensor.current_celsius,
+                sensor.max_celsius,
+                sensor.critical_celsius,
+            );
+            compact_metric(
+                ui,
+                &sensor.label,
+                &format!("{:.1}°C", sensor.current_celsius),
+                color,
+            );
+        }
+
+        for fan in &thermal.fans {
+            compact_metric(ui, &fan.label, &format!("{} rpm", fan.rpm), colors.text_dim);
+        }
+    });
 }